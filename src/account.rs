@@ -0,0 +1,71 @@
+use crate::config::{Config, ConfigManager};
+use crate::error::CliError;
+use crate::login::{hash_password, request_login, warn_change_pwd_hint};
+use crate::output;
+use crate::utils::{build_client, load_credentials, prompt_line};
+use crate::{iprintln, vprintln};
+
+fn prompt_new_password() -> anyhow::Result<Option<String>> {
+    let password = rpassword::prompt_password("New password (leave empty to keep): ")?;
+    if password.is_empty() {
+        return Ok(None);
+    }
+    let confirm = rpassword::prompt_password("Confirm new password: ")?;
+    if password != confirm {
+        anyhow::bail!(CliError::Validation("Passwords do not match".to_string()));
+    }
+    Ok(Some(password))
+}
+
+pub async fn handle_account_update(
+    new_username: Option<String>,
+    new_password: Option<String>,
+) -> anyhow::Result<()> {
+    let credentials = load_credentials()?;
+
+    let (new_username, new_password) = if new_username.is_none() && new_password.is_none() {
+        let username = prompt_line(&format!(
+            "New username (leave empty to keep `{}`): ",
+            credentials.username
+        ))?;
+        let username = (!username.is_empty()).then_some(username);
+        (username, prompt_new_password()?)
+    } else {
+        (new_username, new_password)
+    };
+
+    if new_username.is_none() && new_password.is_none() {
+        anyhow::bail!(CliError::Validation(
+            "Nothing to update: new username and new password are both empty".to_string()
+        ));
+    }
+
+    let current_password = rpassword::prompt_password("Current password: ")?;
+    let current_hash = hash_password(&current_password);
+    let new_password_hash = new_password.as_deref().map(hash_password);
+
+    iprintln!("Updating dashboard account...");
+    let api_client = build_client()?;
+    let ret = api_client
+        .edit_account(
+            &current_hash,
+            new_username.as_deref(),
+            new_password_hash.as_deref(),
+        )
+        .await?;
+    vprintln!("Account edit response: {}", ret);
+
+    // The dashboard credentials changed, log in again to refresh the stored token
+    let username = new_username.unwrap_or_else(|| credentials.username.clone());
+    let password_hash = new_password_hash.unwrap_or(current_hash);
+    let data = request_login(&credentials, username, password_hash).await?;
+    warn_change_pwd_hint(&data);
+
+    ConfigManager::save_credentials(&Config {
+        token: data.token,
+        username: data.username,
+        ..credentials
+    })?;
+    output::print_message("Account updated, stored credentials refreshed");
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use futures::stream::StreamExt;
+use reqwest::{multipart, Method};
+use reqwest_eventsource::{Event, EventSource};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{encode_component, value_to_string, ApiClient, ApiResponse};
+use crate::error::CliError;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChatSession {
+    pub session_id: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub updated_at: Value,
+}
+
+#[derive(Deserialize)]
+struct NewSession {
+    session_id: String,
+}
+
+#[derive(Deserialize)]
+struct UploadedFile {
+    filename: String,
+}
+
+/// A message sent to the web chat
+#[derive(Debug, Default, Serialize)]
+pub struct ChatMessage {
+    pub session_id: String,
+    pub message: String,
+    /// File names returned by the image upload
+    pub image_url: Vec<String>,
+    pub audio_url: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_model: Option<String>,
+}
+
+/// One event of the reply stream
+#[derive(Debug, Deserialize)]
+pub struct ChatEvent {
+    /// `plain`, `image`, `audio`, `complete`, `update_title`, `break` or `end`
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, deserialize_with = "value_to_string")]
+    pub data: String,
+    /// Whether `data` of a `plain` event is a delta of a streamed reply
+    #[serde(default)]
+    pub streaming: bool,
+}
+
+/// Turn a reply that is not an event stream into an API error
+async fn rejection(response: reqwest::Response) -> CliError {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    match serde_json::from_str::<ApiResponse<Value>>(&text) {
+        Ok(reply) if !reply.message.is_empty() => CliError::Api(reply.message),
+        _ => {
+            let snippet = text.chars().take(256).collect::<String>();
+            match status.as_u16() {
+                404 => CliError::NotFound(format!("Chat request failed: HTTP {}", status)),
+                _ => CliError::Api(format!(
+                    "Chat request failed: HTTP {}. Body: {}",
+                    status, snippet
+                )),
+            }
+        }
+    }
+}
+
+impl ApiClient {
+    pub async fn new_chat_session(&self) -> anyhow::Result<String> {
+        let session = self
+            .send_and_parse::<NewSession>(self.request(Method::GET, "/api/chat/new_session"))
+            .await?
+            .into_data()?;
+        Ok(session.session_id)
+    }
+
+    pub async fn get_chat_sessions(&self) -> anyhow::Result<Vec<ChatSession>> {
+        self.send_and_parse::<Vec<ChatSession>>(self.request(Method::GET, "/api/chat/sessions"))
+            .await?
+            .into_data()
+    }
+
+    /// Upload an image for a later message, returns its server side name
+    pub async fn upload_chat_image(
+        &self,
+        file_name: &str,
+        content: Vec<u8>,
+    ) -> anyhow::Result<String> {
+        let part = multipart::Part::bytes(content).file_name(file_name.to_string());
+        let form = multipart::Form::new().part("file", part);
+        let file = self
+            .send_and_parse::<UploadedFile>(
+                self.request(Method::POST, "/api/chat/post_image")
+                    .multipart(form),
+            )
+            .await?
+            .into_data()?;
+        Ok(file.filename)
+    }
+
+    /// URL of a file (e.g. an image) sent by the bot
+    pub fn chat_file_url(&self, file_name: &str) -> String {
+        format!(
+            "{}?filename={}",
+            self.endpoint("/api/chat/get_file"),
+            encode_component(file_name)
+        )
+    }
+
+    /// Send a message and pass every event of the streamed reply to `on_event`
+    pub async fn send_chat_message(
+        &self,
+        message: &ChatMessage,
+        mut on_event: impl FnMut(ChatEvent) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let request_builder = self
+            .untimed_request(Method::POST, "/api/chat/send")?
+            .json(message);
+        let mut es = EventSource::new(request_builder)?;
+        while let Some(event) = es.next().await {
+            match event {
+                Ok(Event::Open) => {}
+                Ok(Event::Message(message)) => {
+                    let event: ChatEvent = serde_json::from_str(&message.data)?;
+                    let done = event.kind == "end";
+                    on_event(event)?;
+                    if done {
+                        break;
+                    }
+                }
+                Err(reqwest_eventsource::Error::StreamEnded) => break,
+                Err(reqwest_eventsource::Error::InvalidStatusCode(status, _))
+                    if status.as_u16() == 401 || status.as_u16() == 403 =>
+                {
+                    es.close();
+                    anyhow::bail!(CliError::Auth(format!(
+                        "Chat request failed: HTTP {} (token expired or invalid? try `astrbot login` again)",
+                        status
+                    )));
+                }
+                // The server answers with plain JSON when it rejects a message
+                Err(reqwest_eventsource::Error::InvalidContentType(_, response))
+                | Err(reqwest_eventsource::Error::InvalidStatusCode(_, response)) => {
+                    es.close();
+                    anyhow::bail!(rejection(response).await);
+                }
+                Err(err) => {
+                    es.close();
+                    anyhow::bail!(CliError::Network(format!("Chat stream failed: {}", err)));
+                }
+            }
+        }
+        es.close();
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::ApiClient;
+
+/// A chat command registered by a plugin handler
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Command {
+    pub handler_full_name: String,
+    #[serde(default)]
+    pub handler_name: String,
+    /// Name of the owning plugin
+    #[serde(default)]
+    pub plugin: String,
+    #[serde(default)]
+    pub description: String,
+    /// `command`, `group` or `sub_command`
+    #[serde(default, rename = "type")]
+    pub kind: String,
+    /// The full trigger, including the names of parent groups
+    #[serde(default)]
+    pub effective_command: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// `everyone` or `admin`, empty when unknown
+    #[serde(default)]
+    pub permission: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing)]
+    pub sub_commands: Vec<Command>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct CommandList {
+    items: Vec<Command>,
+}
+
+impl ApiClient {
+    pub async fn get_commands(&self) -> anyhow::Result<Vec<Command>> {
+        let list = self
+            .send_and_parse::<CommandList>(self.request(Method::GET, "/api/commands"))
+            .await?
+            .into_data()?;
+        Ok(list.items)
+    }
+
+    pub async fn toggle_command(
+        &self,
+        handler_full_name: &str,
+        enabled: bool,
+    ) -> anyhow::Result<String> {
+        let body = json!({ "handler_full_name": handler_full_name, "enabled": enabled });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/commands/toggle")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn rename_command(
+        &self,
+        handler_full_name: &str,
+        new_name: &str,
+        aliases: Option<&[String]>,
+    ) -> anyhow::Result<String> {
+        let mut body = json!({ "handler_full_name": handler_full_name, "new_name": new_name });
+        if let Some(aliases) = aliases {
+            body["aliases"] = json!(aliases);
+        }
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/commands/rename")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+}
@@ -0,0 +1,71 @@
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::ApiClient;
+
+/// AstrBot core configuration together with its schema
+#[derive(Debug, Deserialize)]
+pub struct CoreConfig {
+    pub config: Value,
+    #[serde(default)]
+    pub metadata: Value,
+}
+
+/// Plugin configuration together with its schema, both are null when the
+/// plugin has no configuration
+#[derive(Debug, Deserialize)]
+pub struct PluginConfig {
+    #[serde(default)]
+    pub config: Value,
+    #[serde(default)]
+    pub metadata: Value,
+}
+
+impl ApiClient {
+    pub async fn get_core_config(&self, conf_id: &str) -> anyhow::Result<CoreConfig> {
+        self.send_and_parse::<CoreConfig>(
+            self.request(Method::GET, "/api/config/abconf")
+                .query(&[("id", conf_id)]),
+        )
+        .await?
+        .into_data()
+    }
+
+    pub async fn update_core_config(
+        &self,
+        conf_id: &str,
+        config: &Value,
+    ) -> anyhow::Result<String> {
+        let body = json!({ "conf_id": conf_id, "config": config });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/config/astrbot/update")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn get_plugin_config(&self, plugin_name: &str) -> anyhow::Result<PluginConfig> {
+        self.send_and_parse::<PluginConfig>(
+            self.request(Method::GET, "/api/config/get")
+                .query(&[("plugin_name", plugin_name)]),
+        )
+        .await?
+        .into_data()
+    }
+
+    pub async fn update_plugin_config(
+        &self,
+        plugin_name: &str,
+        config: &Value,
+    ) -> anyhow::Result<String> {
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/config/plugin/update")
+                .query(&[("plugin_name", plugin_name)])
+                .json(config),
+        )
+        .await?
+        .into_message()
+    }
+}
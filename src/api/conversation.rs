@@ -0,0 +1,104 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::ApiClient;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Conversation {
+    pub cid: String,
+    /// Unified message origin of the session, `platform:type:session_id`
+    pub user_id: String,
+    #[serde(default)]
+    pub platform_id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub persona_id: Option<String>,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+    /// Message history, the server sends it as a JSON encoded string
+    #[serde(default)]
+    pub history: Value,
+}
+
+impl Conversation {
+    /// The message history as a list of chat messages
+    pub fn messages(&self) -> Vec<Value> {
+        match &self.history {
+            Value::Array(messages) => messages.clone(),
+            Value::String(text) => serde_json::from_str(text).unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Pagination {
+    #[serde(default)]
+    pub total: u64,
+    #[serde(default)]
+    pub total_pages: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConversationPage {
+    #[serde(default)]
+    pub conversations: Vec<Conversation>,
+    pub pagination: Pagination,
+}
+
+/// Server-side filters of the conversation list
+#[derive(Debug, Default)]
+pub struct ConversationQuery {
+    pub platforms: Vec<String>,
+    pub search: Option<String>,
+    pub page: u64,
+    pub page_size: u64,
+}
+
+impl ApiClient {
+    pub async fn get_conversations(
+        &self,
+        query: &ConversationQuery,
+    ) -> anyhow::Result<ConversationPage> {
+        let mut params = vec![
+            ("page", query.page.to_string()),
+            ("page_size", query.page_size.to_string()),
+        ];
+        if !query.platforms.is_empty() {
+            params.push(("platforms", query.platforms.join(",")));
+        }
+        if let Some(search) = &query.search {
+            params.push(("search", search.clone()));
+        }
+        self.send_and_parse::<ConversationPage>(
+            self.request(Method::GET, "/api/conversation/list")
+                .query(&params),
+        )
+        .await?
+        .into_data()
+    }
+
+    pub async fn get_conversation(&self, user_id: &str, cid: &str) -> anyhow::Result<Conversation> {
+        let body = json!({ "user_id": user_id, "cid": cid });
+        self.send_and_parse::<Conversation>(
+            self.request(Method::POST, "/api/conversation/detail")
+                .json(&body),
+        )
+        .await?
+        .into_data()
+    }
+
+    pub async fn delete_conversation(&self, user_id: &str, cid: &str) -> anyhow::Result<String> {
+        let body = json!({ "user_id": user_id, "cid": cid });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/conversation/delete")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+}
@@ -0,0 +1,102 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::ApiClient;
+
+/// A job of the AstrBot scheduler
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CronJob {
+    pub job_id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub job_type: String,
+    /// Five-field crontab expression, `None` for one-shot jobs
+    #[serde(default)]
+    pub cron_expression: Option<String>,
+    /// IANA time zone the expression is evaluated in, the server's when unset
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub payload: Value,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub last_run_at: Option<String>,
+    #[serde(default)]
+    pub next_run_time: Option<String>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// What a job does when it runs: wake the bot in `session` with `note` as
+/// its instruction
+#[derive(Debug, Serialize)]
+pub struct CronPayload {
+    pub session: String,
+    pub note: String,
+}
+
+/// Settings of a new job
+#[derive(Debug, Serialize)]
+pub struct NewCronJob {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub cron_expression: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    pub payload: CronPayload,
+    pub enabled: bool,
+}
+
+impl ApiClient {
+    pub async fn get_cron_jobs(&self) -> anyhow::Result<Vec<CronJob>> {
+        self.send_and_parse::<Vec<CronJob>>(self.request(Method::GET, "/api/cron/list"))
+            .await?
+            .into_data()
+    }
+
+    /// Create a job, the CLI only creates jobs acting through the agent
+    pub async fn add_cron_job(&self, job: &NewCronJob) -> anyhow::Result<CronJob> {
+        let mut body = json!(job);
+        body["job_type"] = json!("active_agent");
+        self.send_and_parse::<CronJob>(self.request(Method::POST, "/api/cron/add").json(&body))
+            .await?
+            .into_data()
+    }
+
+    pub async fn delete_cron_job(&self, job_id: &str) -> anyhow::Result<String> {
+        let body = json!({ "job_id": job_id });
+        self.send_and_parse::<Value>(self.request(Method::POST, "/api/cron/delete").json(&body))
+            .await?
+            .into_message()
+    }
+
+    /// Pause or resume a job
+    pub async fn set_cron_job_enabled(
+        &self,
+        job_id: &str,
+        enabled: bool,
+    ) -> anyhow::Result<String> {
+        let body = json!({ "job_id": job_id, "enabled": enabled });
+        self.send_and_parse::<Value>(self.request(Method::POST, "/api/cron/update").json(&body))
+            .await?
+            .into_message()
+    }
+
+    /// Run a job once now, its schedule is left unchanged
+    pub async fn run_cron_job(&self, job_id: &str) -> anyhow::Result<String> {
+        let body = json!({ "job_id": job_id });
+        self.send_and_parse::<Value>(self.request(Method::POST, "/api/cron/run").json(&body))
+            .await?
+            .into_message()
+    }
+}
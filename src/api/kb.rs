@@ -0,0 +1,156 @@
+use reqwest::{multipart, Method};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::ApiClient;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct KnowledgeBase {
+    pub kb_id: String,
+    pub kb_name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub embedding_provider_id: Option<String>,
+    #[serde(default)]
+    pub rerank_provider_id: Option<String>,
+    #[serde(default)]
+    pub doc_count: u64,
+    #[serde(default)]
+    pub chunk_count: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct KbDocument {
+    pub doc_id: String,
+    pub doc_name: String,
+    #[serde(default)]
+    pub file_size: u64,
+    #[serde(default)]
+    pub chunk_count: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RetrievalResult {
+    #[serde(default)]
+    pub doc_name: String,
+    #[serde(default)]
+    pub kb_name: String,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub score: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Items<T> {
+    #[serde(default = "Vec::new")]
+    items: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Retrieval {
+    #[serde(default)]
+    results: Vec<RetrievalResult>,
+}
+
+/// Settings of a new knowledge base
+#[derive(Debug, Serialize)]
+pub struct NewKnowledgeBase {
+    pub kb_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub embedding_provider_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rerank_provider_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunk_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunk_overlap: Option<u32>,
+}
+
+/// Page size large enough to fetch every knowledge base or document at once
+const LIST_PAGE_SIZE: &str = "1000";
+
+impl ApiClient {
+    pub async fn get_knowledge_bases(&self) -> anyhow::Result<Vec<KnowledgeBase>> {
+        let list = self
+            .send_and_parse::<Items<KnowledgeBase>>(
+                self.request(Method::GET, "/api/kb/list")
+                    .query(&[("page", "1"), ("page_size", LIST_PAGE_SIZE)]),
+            )
+            .await?
+            .into_data()?;
+        Ok(list.items)
+    }
+
+    pub async fn create_knowledge_base(&self, kb: &NewKnowledgeBase) -> anyhow::Result<String> {
+        self.send_and_parse::<Value>(self.request(Method::POST, "/api/kb/create").json(kb))
+            .await?
+            .into_message()
+    }
+
+    pub async fn delete_knowledge_base(&self, kb_id: &str) -> anyhow::Result<String> {
+        let body = json!({ "kb_id": kb_id });
+        self.send_and_parse::<Value>(self.request(Method::POST, "/api/kb/delete").json(&body))
+            .await?
+            .into_message()
+    }
+
+    pub async fn get_kb_documents(&self, kb_id: &str) -> anyhow::Result<Vec<KbDocument>> {
+        let list = self
+            .send_and_parse::<Items<KbDocument>>(
+                self.request(Method::GET, "/api/kb/document/list").query(&[
+                    ("kb_id", kb_id),
+                    ("page", "1"),
+                    ("page_size", LIST_PAGE_SIZE),
+                ]),
+            )
+            .await?
+            .into_data()?;
+        Ok(list.items)
+    }
+
+    pub async fn upload_kb_document(
+        &self,
+        kb_id: &str,
+        file_name: &str,
+        content: Vec<u8>,
+    ) -> anyhow::Result<String> {
+        let part = multipart::Part::bytes(content).file_name(file_name.to_string());
+        let form = multipart::Form::new()
+            .text("kb_id", kb_id.to_string())
+            .part("file", part);
+
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/kb/document/upload")
+                .multipart(form),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn delete_kb_document(&self, kb_id: &str, doc_id: &str) -> anyhow::Result<String> {
+        let body = json!({ "kb_id": kb_id, "doc_id": doc_id });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/kb/document/delete")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn retrieve_kb(
+        &self,
+        kb_name: &str,
+        query: &str,
+        top_k: u32,
+    ) -> anyhow::Result<Vec<RetrievalResult>> {
+        let body = json!({ "query": query, "kb_names": [kb_name], "top_k": top_k });
+        let retrieval = self
+            .send_and_parse::<Retrieval>(self.request(Method::POST, "/api/kb/retrieve").json(&body))
+            .await?
+            .into_data()?;
+        Ok(retrieval.results)
+    }
+}
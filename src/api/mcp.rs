@@ -0,0 +1,64 @@
+use reqwest::Method;
+use serde_json::{json, Map, Value};
+
+use super::ApiClient;
+
+impl ApiClient {
+    /// List MCP servers, each is its config plus `name`, `active` and `tools`
+    pub async fn get_mcp_servers(&self) -> anyhow::Result<Vec<Value>> {
+        self.send_and_parse::<Vec<Value>>(self.request(Method::GET, "/api/tools/mcp/servers"))
+            .await?
+            .into_data()
+    }
+
+    pub async fn add_mcp_server(
+        &self,
+        name: &str,
+        active: bool,
+        config: &Map<String, Value>,
+    ) -> anyhow::Result<String> {
+        let mut body = config.clone();
+        body.insert("name".to_string(), json!(name));
+        body.insert("active".to_string(), json!(active));
+        self.send_and_parse::<Value>(self.request(Method::POST, "/api/tools/mcp/add").json(&body))
+            .await?
+            .into_message()
+    }
+
+    pub async fn update_mcp_server(
+        &self,
+        name: &str,
+        active: bool,
+        config: &Map<String, Value>,
+    ) -> anyhow::Result<String> {
+        let mut body = config.clone();
+        body.insert("name".to_string(), json!(name));
+        body.insert("active".to_string(), json!(active));
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/tools/mcp/update")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn delete_mcp_server(&self, name: &str) -> anyhow::Result<String> {
+        let body = json!({ "name": name });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/tools/mcp/delete")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn test_mcp_server(&self, config: &Map<String, Value>) -> anyhow::Result<String> {
+        let body = json!({ "mcp_server_config": config });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/tools/mcp/test")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+}
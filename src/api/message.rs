@@ -0,0 +1,38 @@
+use reqwest::{multipart, Method};
+use serde_json::Value;
+
+use super::ApiClient;
+
+/// An image attached to a proactive message
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub file_name: String,
+    pub content: Vec<u8>,
+}
+
+impl ApiClient {
+    /// Send a message to a session through its platform adapter, without a
+    /// user message to reply to
+    pub async fn send_message(
+        &self,
+        umo: &str,
+        text: &str,
+        image: Option<&Attachment>,
+    ) -> anyhow::Result<String> {
+        let mut form = multipart::Form::new()
+            .text("umo", umo.to_string())
+            .text("text", text.to_string());
+        if let Some(image) = image {
+            let part =
+                multipart::Part::bytes(image.content.clone()).file_name(image.file_name.clone());
+            form = form.part("image", part);
+        }
+
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/message/send")
+                .multipart(form),
+        )
+        .await?
+        .into_message()
+    }
+}
@@ -0,0 +1,391 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::sync::OnceLock;
+
+use futures::stream::StreamExt;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::{multipart, Method};
+use reqwest_eventsource::{Event, EventSource};
+use serde::{de::DeserializeOwned, Deserialize};
+
+mod chat;
+mod command;
+mod config;
+mod conversation;
+mod cron;
+mod kb;
+mod mcp;
+mod message;
+mod persona;
+mod platform;
+mod provider;
+mod session;
+mod t2i;
+mod tool;
+mod update;
+
+pub use chat::{ChatEvent, ChatMessage};
+pub use command::Command;
+pub use conversation::{Conversation, ConversationQuery};
+pub use cron::{CronJob, CronPayload, NewCronJob};
+pub use kb::{KnowledgeBase, NewKnowledgeBase};
+pub use message::Attachment;
+pub use persona::Persona;
+pub use provider::PROVIDER_TYPES;
+pub use session::Session;
+pub use t2i::DEFAULT_T2I_ENDPOINT;
+pub use tool::FunctionTool;
+pub use update::Release;
+
+use crate::{
+    config::Config, error::CliError, http, iprintln, plugin::Plugin, stat::Stat,
+    utils::mask_secret, vprintln,
+};
+
+/// Characters left as is when a value is put into a URL path or query
+const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Percent-encode a value for use as one URL path segment or query value
+fn encode_component(value: &str) -> String {
+    utf8_percent_encode(value, URL_COMPONENT).to_string()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LogHistory {
+    pub logs: Vec<LogRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum LogRecord {
+    Text(String),
+    Object(serde_json::Value),
+}
+
+impl LogRecord {
+    pub fn into_line(self) -> String {
+        match self {
+            LogRecord::Text(text) => text,
+            LogRecord::Object(value) => value.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct LiveLogMessage {
+    #[serde(rename = "type")]
+    typo: String,
+    level: String,
+    time: String,
+    data: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ApiResponse<T> {
+    pub status: String,
+    #[serde(default, deserialize_with = "value_to_string")]
+    pub message: String,
+    pub data: Option<T>,
+}
+
+fn value_to_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let opt = Option::<serde_json::Value>::deserialize(deserializer)?;
+    let value = opt.unwrap_or(serde_json::Value::Null);
+    match value {
+        serde_json::Value::Null => Ok(String::new()),
+        serde_json::Value::String(s) => Ok(s),
+        other => Ok(other.to_string()),
+    }
+}
+
+impl<T> ApiResponse<T> {
+    /// Most routes answer `ok`, a few (e.g. the update check) `success`
+    fn is_ok(&self) -> bool {
+        self.status.eq_ignore_ascii_case("ok") || self.status.eq_ignore_ascii_case("success")
+    }
+
+    /// Return the payload of a successful response
+    fn into_data(self) -> anyhow::Result<T> {
+        if !self.is_ok() {
+            anyhow::bail!(CliError::Api(self.message));
+        }
+        self.data
+            .ok_or_else(|| CliError::Api("No data received".to_string()).into())
+    }
+
+    /// Return the message of a successful response
+    fn into_message(self) -> anyhow::Result<String> {
+        if !self.is_ok() {
+            anyhow::bail!(CliError::Api(self.message));
+        }
+        Ok(self.message)
+    }
+}
+
+pub struct ApiClient {
+    base_url: String,
+    client: reqwest::Client,
+    /// Client without the read timeout, see [`http::build_untimed_http_client`],
+    /// built on first use
+    untimed_client: OnceLock<reqwest::Client>,
+    profile: Config,
+    token: String,
+    headers: BTreeMap<String, String>,
+}
+
+impl ApiClient {
+    pub fn new(profile: &Config) -> anyhow::Result<Self> {
+        Ok(Self {
+            base_url: profile.server_url.trim_end_matches('/').to_string(),
+            client: http::build_http_client(profile)?,
+            untimed_client: OnceLock::new(),
+            profile: profile.clone(),
+            token: profile.token.clone(),
+            headers: profile.headers.clone(),
+        })
+    }
+
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    fn token_preview(&self) -> String {
+        mask_secret(&self.token)
+    }
+
+    fn request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        self.request_with(&self.client, method, path)
+    }
+
+    /// Build a request that may wait for the server indefinitely, e.g. a
+    /// live event stream
+    fn untimed_request(
+        &self,
+        method: Method,
+        path: &str,
+    ) -> anyhow::Result<reqwest::RequestBuilder> {
+        let client = match self.untimed_client.get() {
+            Some(client) => client,
+            None => {
+                let client = http::build_untimed_http_client(&self.profile)?;
+                self.untimed_client.get_or_init(|| client)
+            }
+        };
+        Ok(self.request_with(client, method, path))
+    }
+
+    fn request_with(
+        &self,
+        client: &reqwest::Client,
+        method: Method,
+        path: &str,
+    ) -> reqwest::RequestBuilder {
+        let url = self.endpoint(path);
+        vprintln!("{} {}", method.as_str(), url);
+        vprintln!("Token: {}", self.token_preview());
+
+        let builder = http::apply_headers(client.request(method, url), &self.headers);
+        builder.header("Authorization", format!("Bearer {}", self.token))
+    }
+
+    async fn send_and_parse<T: DeserializeOwned>(
+        &self,
+        builder: reqwest::RequestBuilder,
+    ) -> anyhow::Result<ApiResponse<T>> {
+        let response = http::send(builder).await.map_err(CliError::network)?;
+        let status = response.status();
+        let text = response.text().await.map_err(CliError::network)?;
+        let snippet = || text.chars().take(256).collect::<String>();
+
+        if !status.is_success() {
+            let snippet = snippet();
+            let message = format!("API request failed: HTTP {}. Body: {}", status, snippet);
+            match status.as_u16() {
+                401 | 403 => anyhow::bail!(CliError::Auth(format!(
+                    "{} (token expired or invalid? try `astrbot login` again)",
+                    message
+                ))),
+                404 => anyhow::bail!(CliError::NotFound(message)),
+                _ => anyhow::bail!(CliError::Api(message)),
+            }
+        }
+
+        let raw_json: ApiResponse<T> = serde_json::from_str(&text).map_err(|e| {
+            CliError::Api(format!("Unexpected response: {}. Body: {}", e, snippet()))
+        })?;
+        vprintln!("ApiResponse.status : {}", raw_json.status);
+        vprintln!("ApiResponse.message: {}", raw_json.message);
+
+        Ok(raw_json)
+    }
+
+    pub async fn get_plugins(&self) -> anyhow::Result<Vec<Plugin>> {
+        let resp = self
+            .send_and_parse::<Vec<Plugin>>(self.request(Method::GET, "/api/plugin/get"))
+            .await?;
+
+        if resp.is_ok() {
+            Ok(resp.data.unwrap_or_default())
+        } else {
+            anyhow::bail!(CliError::Api(resp.message));
+        }
+    }
+
+    pub async fn install_local_plugin(&self, local_plugin: &String) -> anyhow::Result<String> {
+        let form = multipart::Form::new().file("file", local_plugin).await?;
+
+        let resp = self
+            .send_and_parse::<serde_json::Value>(
+                self.request(Method::POST, "/api/plugin/install-upload")
+                    .multipart(form),
+            )
+            .await?;
+
+        if resp.is_ok() {
+            Ok(resp.message)
+        } else {
+            anyhow::bail!(CliError::Api(resp.message));
+        }
+    }
+
+    pub async fn install_remote_plugin(&self, remote_plugin: &str) -> anyhow::Result<String> {
+        let mut body: HashMap<&str, &str> = HashMap::with_capacity(2);
+        body.insert("proxy", "");
+        body.insert("url", remote_plugin);
+        let resp = self
+            .send_and_parse::<serde_json::Value>(
+                self.request(Method::POST, "/api/plugin/install")
+                    .json(&body),
+            )
+            .await?;
+
+        if resp.is_ok() {
+            Ok(resp.message)
+        } else {
+            anyhow::bail!(CliError::Api(resp.message));
+        }
+    }
+
+    pub async fn plugin_common_actions_request(
+        &self,
+        plugin_name: &str,
+        action_name: &str,
+    ) -> anyhow::Result<String> {
+        let mut body: HashMap<&str, &str> = HashMap::with_capacity(1);
+        body.insert("name", plugin_name);
+        let path = format!("/api/plugin/{}", action_name);
+        let resp = self
+            .send_and_parse::<serde_json::Value>(self.request(Method::POST, &path).json(&body))
+            .await?;
+
+        if resp.is_ok() {
+            Ok(resp.message)
+        } else {
+            anyhow::bail!(CliError::Api(resp.message));
+        }
+    }
+
+    pub async fn edit_account(
+        &self,
+        password_hash: &str,
+        new_username: Option<&str>,
+        new_password_hash: Option<&str>,
+    ) -> anyhow::Result<String> {
+        let mut body: HashMap<&str, &str> = HashMap::with_capacity(3);
+        body.insert("password", password_hash);
+        if let Some(new_username) = new_username {
+            body.insert("new_username", new_username);
+        }
+        if let Some(new_password_hash) = new_password_hash {
+            body.insert("new_password", new_password_hash);
+        }
+        let resp = self
+            .send_and_parse::<serde_json::Value>(
+                self.request(Method::POST, "/api/auth/account/edit")
+                    .json(&body),
+            )
+            .await?;
+
+        if resp.is_ok() {
+            Ok(resp.message)
+        } else {
+            anyhow::bail!(CliError::Api(resp.message));
+        }
+    }
+
+    pub async fn get_stat(&self) -> anyhow::Result<Stat> {
+        let resp = self
+            .send_and_parse::<Stat>(self.request(Method::GET, "/api/stat/get"))
+            .await?;
+
+        if resp.is_ok() {
+            match resp.data {
+                Some(stat) => Ok(stat),
+                None => anyhow::bail!(CliError::Api("No data received".to_string())),
+            }
+        } else {
+            anyhow::bail!(CliError::Api(resp.message));
+        }
+    }
+
+    /// Ask AstrBot core to restart, the request returns before it goes down
+    pub async fn restart_core(&self) -> anyhow::Result<String> {
+        self.send_and_parse::<serde_json::Value>(
+            self.request(Method::POST, "/api/stat/restart-core"),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn get_log_history(&self) -> anyhow::Result<LogHistory> {
+        let resp = self
+            .send_and_parse::<LogHistory>(self.request(Method::GET, "/api/log-history"))
+            .await?;
+        if resp.is_ok() {
+            match resp.data {
+                Some(data) => Ok(data),
+                None => anyhow::bail!(CliError::Api("No data received".to_string())),
+            }
+        } else {
+            anyhow::bail!(CliError::Api(resp.message));
+        }
+    }
+
+    pub async fn get_live_log(&self, flush: bool) -> anyhow::Result<()> {
+        let request_builder = self.untimed_request(Method::GET, "api/live-log")?;
+        let mut es = EventSource::new(request_builder)?;
+        let mut out = std::io::stdout().lock();
+        while let Some(event) = es.next().await {
+            match event {
+                Ok(ev) => match ev {
+                    Event::Open => {
+                        iprintln!("Start to print live log");
+                    }
+                    Event::Message(message) => {
+                        let message: LiveLogMessage = serde_json::from_str(&message.data)?;
+                        if flush {
+                            write!(out, "\x1B[2J\x1B[1;1H")?;
+                        }
+                        writeln!(out, "{}", message.data)?;
+                    }
+                },
+                Err(err) => {
+                    es.close();
+                    anyhow::bail!(CliError::Network(format!(
+                        "Live log stream failed: {}",
+                        err
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::ApiClient;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Persona {
+    pub persona_id: String,
+    #[serde(default)]
+    pub system_prompt: String,
+    /// Alternating user/assistant messages prepended to every conversation
+    #[serde(default)]
+    pub begin_dialogs: Vec<String>,
+    /// Allowed tools, `None` means all tools
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+}
+
+impl ApiClient {
+    pub async fn get_personas(&self) -> anyhow::Result<Vec<Persona>> {
+        self.send_and_parse::<Vec<Persona>>(self.request(Method::GET, "/api/persona/list"))
+            .await?
+            .into_data()
+    }
+
+    pub async fn get_persona(&self, persona_id: &str) -> anyhow::Result<Persona> {
+        let body = json!({ "persona_id": persona_id });
+        self.send_and_parse::<Persona>(
+            self.request(Method::POST, "/api/persona/detail")
+                .json(&body),
+        )
+        .await?
+        .into_data()
+    }
+
+    pub async fn create_persona(&self, persona: &Persona) -> anyhow::Result<String> {
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/persona/create")
+                .json(persona),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn update_persona(&self, persona: &Persona) -> anyhow::Result<String> {
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/persona/update")
+                .json(persona),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn delete_persona(&self, persona_id: &str) -> anyhow::Result<String> {
+        let body = json!({ "persona_id": persona_id });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/persona/delete")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+}
@@ -0,0 +1,50 @@
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::ApiClient;
+
+#[derive(Debug, Deserialize)]
+struct PlatformList {
+    #[serde(default)]
+    platforms: Vec<Value>,
+}
+
+impl ApiClient {
+    pub async fn get_platforms(&self) -> anyhow::Result<Vec<Value>> {
+        let list = self
+            .send_and_parse::<PlatformList>(self.request(Method::GET, "/api/config/platform/list"))
+            .await?
+            .into_data()?;
+        Ok(list.platforms)
+    }
+
+    pub async fn create_platform(&self, config: &Value) -> anyhow::Result<String> {
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/config/platform/new")
+                .json(config),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn update_platform(&self, id: &str, config: &Value) -> anyhow::Result<String> {
+        let body = json!({ "id": id, "config": config });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/config/platform/update")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn delete_platform(&self, id: &str) -> anyhow::Result<String> {
+        let body = json!({ "id": id });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/config/platform/delete")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+}
@@ -0,0 +1,88 @@
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::ApiClient;
+
+/// Provider types known to the dashboard
+pub const PROVIDER_TYPES: &[&str] = &[
+    "chat_completion",
+    "speech_to_text",
+    "text_to_speech",
+    "embedding",
+    "rerank",
+];
+
+/// Result of the server-side connectivity check
+#[derive(Debug, Deserialize)]
+pub struct ProviderStatus {
+    pub id: String,
+    pub status: String,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProviderModels {
+    #[serde(default)]
+    pub models: Vec<String>,
+}
+
+impl ApiClient {
+    /// List provider configs of the given types (comma separated)
+    pub async fn get_providers(&self, provider_types: &str) -> anyhow::Result<Vec<Value>> {
+        self.send_and_parse::<Vec<Value>>(
+            self.request(Method::GET, "/api/config/provider/list")
+                .query(&[("provider_type", provider_types)]),
+        )
+        .await?
+        .into_data()
+    }
+
+    pub async fn create_provider(&self, config: &Value) -> anyhow::Result<String> {
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/config/provider/new")
+                .json(config),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn update_provider(&self, id: &str, config: &Value) -> anyhow::Result<String> {
+        let body = json!({ "id": id, "config": config });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/config/provider/update")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn delete_provider(&self, id: &str) -> anyhow::Result<String> {
+        let body = json!({ "id": id });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/config/provider/delete")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn check_provider(&self, id: &str) -> anyhow::Result<ProviderStatus> {
+        self.send_and_parse::<ProviderStatus>(
+            self.request(Method::GET, "/api/config/provider/check_one")
+                .query(&[("id", id)]),
+        )
+        .await?
+        .into_data()
+    }
+
+    pub async fn get_provider_models(&self, id: &str) -> anyhow::Result<ProviderModels> {
+        self.send_and_parse::<ProviderModels>(
+            self.request(Method::GET, "/api/config/provider/model_list")
+                .query(&[("provider_id", id)]),
+        )
+        .await?
+        .into_data()
+    }
+}
@@ -0,0 +1,132 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::ApiClient;
+
+/// A conversation the bot takes part in, identified by its unified message
+/// origin (`platform:message_type:session`)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Session {
+    pub session_id: String,
+    #[serde(default)]
+    pub session_name: String,
+    #[serde(default)]
+    pub platform: String,
+    /// `FriendMessage` or `GroupMessage`
+    #[serde(default)]
+    pub message_type: String,
+    #[serde(default)]
+    pub persona_id: Option<String>,
+    #[serde(default)]
+    pub chat_provider_id: Option<String>,
+    /// A disabled session is ignored by the bot entirely
+    #[serde(default = "default_enabled")]
+    pub session_enabled: bool,
+    #[serde(default = "default_enabled")]
+    pub llm_enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+pub struct SessionList {
+    pub sessions: Vec<Session>,
+    #[serde(default)]
+    pub available_personas: Vec<Value>,
+    #[serde(default)]
+    pub available_chat_providers: Vec<Value>,
+    #[serde(default)]
+    pagination: Option<Pagination>,
+}
+
+#[derive(Deserialize)]
+struct Pagination {
+    #[serde(default)]
+    total_pages: Option<u64>,
+}
+
+const LIST_PAGE_SIZE: usize = 100;
+
+impl ApiClient {
+    /// Read every session, one page after another
+    pub async fn get_sessions(&self) -> anyhow::Result<SessionList> {
+        let mut page = 1u64;
+        let mut list = self.get_session_page(page).await?;
+        let mut page_len = list.sessions.len();
+        loop {
+            // Servers without pagination info stop at a short page
+            let last = match list.pagination.as_ref().and_then(|p| p.total_pages) {
+                Some(total_pages) => page >= total_pages,
+                None => page_len < LIST_PAGE_SIZE,
+            };
+            if last {
+                return Ok(list);
+            }
+            page += 1;
+            let next = self.get_session_page(page).await?;
+            page_len = next.sessions.len();
+            let known = list.sessions.len();
+            for session in next.sessions {
+                if !list
+                    .sessions
+                    .iter()
+                    .any(|s| s.session_id == session.session_id)
+                {
+                    list.sessions.push(session);
+                }
+            }
+            // A server ignoring the page number repeats the same sessions
+            if list.sessions.len() == known {
+                return Ok(list);
+            }
+            list.pagination = next.pagination;
+        }
+    }
+
+    async fn get_session_page(&self, page: u64) -> anyhow::Result<SessionList> {
+        self.send_and_parse::<SessionList>(self.request(Method::GET, "/api/session/list").query(&[
+            ("page", page.to_string()),
+            ("page_size", LIST_PAGE_SIZE.to_string()),
+        ]))
+        .await?
+        .into_data()
+    }
+
+    async fn update_session(&self, route: &str, body: Value) -> anyhow::Result<String> {
+        let path = format!("/api/session/{}", route);
+        self.send_and_parse::<Value>(self.request(Method::POST, &path).json(&body))
+            .await?
+            .into_message()
+    }
+
+    pub async fn set_session_persona(&self, umo: &str, persona: &str) -> anyhow::Result<String> {
+        let body = json!({ "session_id": umo, "persona_name": persona });
+        self.update_session("update_persona", body).await
+    }
+
+    pub async fn set_session_provider(
+        &self,
+        umo: &str,
+        provider_id: &str,
+    ) -> anyhow::Result<String> {
+        let body = json!({
+            "session_id": umo,
+            "provider_id": provider_id,
+            "provider_type": "chat_completion",
+        });
+        self.update_session("update_provider", body).await
+    }
+
+    pub async fn set_session_llm(&self, umo: &str, enabled: bool) -> anyhow::Result<String> {
+        let body = json!({ "session_id": umo, "enabled": enabled });
+        self.update_session("update_llm", body).await
+    }
+
+    pub async fn set_session_enabled(&self, umo: &str, enabled: bool) -> anyhow::Result<String> {
+        let body = json!({ "session_id": umo, "session_enabled": enabled });
+        self.update_session("update_status", body).await
+    }
+}
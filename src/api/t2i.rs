@@ -0,0 +1,123 @@
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{encode_component, ApiClient};
+use crate::error::CliError;
+use crate::http;
+
+/// Renderer AstrBot uses when the core config sets no `t2i_endpoint`
+pub const DEFAULT_T2I_ENDPOINT: &str = "https://t2i.soulter.top/text2img";
+
+/// An HTML template of the text-to-image renderer
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct T2iTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub is_active: bool,
+}
+
+#[derive(Deserialize)]
+struct TemplateContent {
+    content: String,
+}
+
+impl ApiClient {
+    pub async fn get_t2i_templates(&self) -> anyhow::Result<Vec<T2iTemplate>> {
+        self.send_and_parse::<Vec<T2iTemplate>>(self.request(Method::GET, "/api/t2i/templates"))
+            .await?
+            .into_data()
+    }
+
+    pub async fn get_t2i_template(&self, name: &str) -> anyhow::Result<String> {
+        let path = format!("/api/t2i/templates/{}", encode_component(name));
+        let template = self
+            .send_and_parse::<TemplateContent>(self.request(Method::GET, &path))
+            .await?
+            .into_data()?;
+        Ok(template.content)
+    }
+
+    pub async fn create_t2i_template(&self, name: &str, content: &str) -> anyhow::Result<String> {
+        let body = json!({ "name": name, "content": content });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/t2i/templates/create")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+
+    pub async fn update_t2i_template(&self, name: &str, content: &str) -> anyhow::Result<String> {
+        let path = format!("/api/t2i/templates/{}", encode_component(name));
+        let body = json!({ "content": content });
+        self.send_and_parse::<Value>(self.request(Method::PUT, &path).json(&body))
+            .await?
+            .into_message()
+    }
+
+    pub async fn set_active_t2i_template(&self, name: &str) -> anyhow::Result<String> {
+        let body = json!({ "name": name });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/t2i/templates/set_active")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+
+    /// Restore the built-in template and make it the active one
+    pub async fn reset_t2i_template(&self) -> anyhow::Result<String> {
+        self.send_and_parse::<Value>(self.request(Method::POST, "/api/t2i/templates/reset_default"))
+            .await?
+            .into_message()
+    }
+
+    /// Render `text` with an HTML template on a text-to-image service.
+    ///
+    /// The service is not the AstrBot server, so neither the token, the
+    /// profile headers nor the profile's TLS and proxy settings are used.
+    pub async fn render_t2i(
+        &self,
+        endpoint: &str,
+        template: &str,
+        text: &str,
+        version: &str,
+        image_type: &str,
+    ) -> anyhow::Result<Vec<u8>> {
+        let url = format!("{}/generate", endpoint.trim_end_matches('/'));
+        let body = json!({
+            "tmpl": template,
+            "tmpldata": { "text": text, "version": version },
+            "json": false,
+            "options": { "full_page": true, "type": image_type },
+        });
+        let client = http::build_plain_http_client()?;
+        let response = http::send(client.post(&url).json(&body))
+            .await
+            .map_err(CliError::network)?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            let snippet = text.chars().take(256).collect::<String>();
+            anyhow::bail!(CliError::Api(format!(
+                "Rendering failed: HTTP {}. Body: {}",
+                status, snippet
+            )));
+        }
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        if !content_type.starts_with("image/") {
+            anyhow::bail!(CliError::Api(format!(
+                "Rendering failed: expected an image, got `{}`",
+                content_type
+            )));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+}
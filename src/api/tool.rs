@@ -0,0 +1,42 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::ApiClient;
+
+/// A function tool the LLM can call
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FunctionTool {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// JSON schema of the call arguments
+    #[serde(default)]
+    pub parameters: Value,
+    #[serde(default)]
+    pub active: bool,
+    /// Where the tool comes from: `plugin`, `mcp` or `unknown`
+    #[serde(default)]
+    pub origin: String,
+    /// Name of the plugin or MCP server providing the tool
+    #[serde(default)]
+    pub origin_name: String,
+}
+
+impl ApiClient {
+    pub async fn get_tools(&self) -> anyhow::Result<Vec<FunctionTool>> {
+        self.send_and_parse::<Vec<FunctionTool>>(self.request(Method::GET, "/api/tools/list"))
+            .await?
+            .into_data()
+    }
+
+    pub async fn toggle_tool(&self, name: &str, activate: bool) -> anyhow::Result<String> {
+        let body = json!({ "name": name, "activate": activate });
+        self.send_and_parse::<Value>(
+            self.request(Method::POST, "/api/tools/toggle-tool")
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+}
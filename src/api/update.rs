@@ -0,0 +1,80 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::ApiClient;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpdateStatus {
+    /// Running AstrBot version, e.g. `v4.5.0`
+    pub version: String,
+    pub has_new_version: bool,
+    #[serde(default)]
+    pub dashboard_version: Option<String>,
+    #[serde(default)]
+    pub dashboard_has_new_version: bool,
+}
+
+/// A published AstrBot release, newest first in the release list
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Release {
+    #[serde(default)]
+    pub tag_name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub published_at: String,
+    /// Changelog in Markdown
+    #[serde(default)]
+    pub body: String,
+}
+
+impl Release {
+    pub fn tag(&self) -> &str {
+        if self.tag_name.is_empty() {
+            &self.version
+        } else {
+            &self.tag_name
+        }
+    }
+}
+
+impl ApiClient {
+    /// Check for updates, also returning the server's description of the result
+    pub async fn check_update(&self) -> anyhow::Result<(UpdateStatus, String)> {
+        let resp = self
+            .send_and_parse::<UpdateStatus>(self.request(Method::GET, "/api/update/check"))
+            .await?;
+        let message = resp.message.clone();
+        Ok((resp.into_data()?, message))
+    }
+
+    pub async fn get_releases(&self) -> anyhow::Result<Vec<Release>> {
+        self.send_and_parse::<Vec<Release>>(self.request(Method::GET, "/api/update/releases"))
+            .await?
+            .into_data()
+    }
+
+    /// Update the core to `version` (`latest` or a release tag), the server
+    /// restarts once done.
+    ///
+    /// The server answers only after downloading the release, so the read
+    /// timeout does not apply.
+    pub async fn update_core(&self, version: &str) -> anyhow::Result<String> {
+        let body = json!({ "version": version, "proxy": "" });
+        self.send_and_parse::<Value>(
+            self.untimed_request(Method::POST, "/api/update/do")?
+                .json(&body),
+        )
+        .await?
+        .into_message()
+    }
+
+    /// Download and install the latest dashboard, without the read timeout
+    /// for the same reason as [`ApiClient::update_core`]
+    pub async fn update_dashboard(&self) -> anyhow::Result<String> {
+        self.send_and_parse::<Value>(self.untimed_request(Method::POST, "/api/update/dashboard")?)
+            .await?
+            .into_message()
+    }
+}
@@ -0,0 +1,830 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::api::{ApiClient, KnowledgeBase, NewKnowledgeBase, Persona, PROVIDER_TYPES};
+use crate::cli::BackupSection;
+use crate::error::CliError;
+use crate::mcp::{is_active, server_config, server_name};
+use crate::utils::{
+    build_client, confirm, is_secret_field, is_secret_map, load_credentials, private_open_options,
+    strf_timestamp,
+};
+use crate::{iprintln, output, vprintln};
+
+/// Version of the archive layout, bumped on incompatible changes
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+/// Core config file the backup covers
+const CONF_ID: &str = "default";
+/// Stands in for a secret left out of the archive
+const REDACTED: &str = "__redacted__";
+/// Core config keys bound to one instance (dashboard login, bind address,
+/// JWT secret), neither backed up nor restored
+const INSTANCE_KEYS: [&str; 1] = ["dashboard"];
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    format_version: u32,
+    /// Unix time the backup was taken
+    created_at: i64,
+    cli_version: String,
+    server_url: String,
+    #[serde(default)]
+    astrbot_version: Option<String>,
+    sections: Vec<String>,
+    secrets_included: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PluginEntry {
+    name: String,
+    version: String,
+    #[serde(default)]
+    repo: String,
+    activated: bool,
+}
+
+pub fn selected_sections(only: &[BackupSection]) -> Vec<BackupSection> {
+    BackupSection::ALL
+        .into_iter()
+        .filter(|s| only.is_empty() || only.contains(s))
+        .collect()
+}
+
+fn file_name(section: BackupSection) -> String {
+    format!("{}.json", section.name())
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Replace every string below `value` with a placeholder
+fn redact_all(value: &mut Value) {
+    match value {
+        Value::String(s) if !s.is_empty() => *s = REDACTED.to_string(),
+        Value::Object(map) => map.values_mut().for_each(redact_all),
+        Value::Array(items) => items.iter_mut().for_each(redact_all),
+        _ => {}
+    }
+}
+
+/// Replace every credential-like field with a placeholder
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (name, field) in map.iter_mut() {
+                if is_secret_map(name) {
+                    redact_all(field);
+                    continue;
+                }
+                if !is_secret_field(name) {
+                    redact(field);
+                    continue;
+                }
+                match field {
+                    Value::String(s) if !s.is_empty() => *s = REDACTED.to_string(),
+                    Value::Array(items) => {
+                        for item in items {
+                            if let Value::String(s) = item {
+                                *s = REDACTED.to_string();
+                            }
+                        }
+                    }
+                    other => redact(other),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Put back the secrets of `current` where the archive has placeholders.
+///
+/// Placeholders without a current value are emptied and their paths
+/// collected into `missing`.
+fn fill_redacted(
+    value: &mut Value,
+    current: Option<&Value>,
+    path: &str,
+    missing: &mut Vec<String>,
+) {
+    match value {
+        Value::String(s) if s == REDACTED => {
+            match current.and_then(Value::as_str).filter(|c| *c != REDACTED) {
+                Some(secret) => *s = secret.to_string(),
+                None => {
+                    s.clear();
+                    missing.push(path.to_string());
+                }
+            }
+        }
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                let current = current.and_then(|c| c.get(key));
+                fill_redacted(field, current, &join_path(path, key), missing);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                let current = current.and_then(|c| c.get(i));
+                fill_redacted(item, current, &join_path(path, &i.to_string()), missing);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn strip_instance_keys(config: &mut Value) {
+    if let Value::Object(map) = config {
+        for key in INSTANCE_KEYS {
+            map.remove(key);
+        }
+    }
+}
+
+/// Read the current state of one section
+pub async fn collect(api_client: &ApiClient, section: BackupSection) -> anyhow::Result<Value> {
+    Ok(match section {
+        BackupSection::CoreConfig => {
+            let mut config = api_client.get_core_config(CONF_ID).await?.config;
+            strip_instance_keys(&mut config);
+            config
+        }
+        BackupSection::Providers => {
+            json!(api_client.get_providers(&PROVIDER_TYPES.join(",")).await?)
+        }
+        BackupSection::Platforms => json!(api_client.get_platforms().await?),
+        BackupSection::Personas => json!(api_client.get_personas().await?),
+        BackupSection::Mcp => {
+            let servers: Vec<Value> = api_client
+                .get_mcp_servers()
+                .await?
+                .iter()
+                .map(|server| {
+                    let mut entry = server_config(server);
+                    entry.insert("name".to_string(), json!(server_name(server)));
+                    entry.insert("active".to_string(), json!(is_active(server)));
+                    Value::Object(entry)
+                })
+                .collect();
+            json!(servers)
+        }
+        BackupSection::Plugins => {
+            let plugins: Vec<PluginEntry> = api_client
+                .get_plugins()
+                .await?
+                .into_iter()
+                .map(|p| PluginEntry {
+                    name: p.name,
+                    version: p.version,
+                    repo: p.repo,
+                    activated: p.activated,
+                })
+                .collect();
+            json!(plugins)
+        }
+        BackupSection::PluginConfigs => {
+            let mut configs = Map::new();
+            for plugin in api_client.get_plugins().await? {
+                let config = api_client
+                    .get_plugin_config(&plugin.name)
+                    .await
+                    .with_context(|| format!("Failed to read the config of {}", plugin.name))?
+                    .config;
+                if config.is_object() {
+                    configs.insert(plugin.name, config);
+                }
+            }
+            Value::Object(configs)
+        }
+        BackupSection::Kb => json!(api_client.get_knowledge_bases().await?),
+    })
+}
+
+fn write_archive(path: &Path, files: &[(String, Vec<u8>)], mtime: u64) -> io::Result<()> {
+    let file = private_open_options()
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    // The mode only applies to new files, tighten an overwritten one too
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    let encoder = zstd::Encoder::new(file, 3)?;
+    let mut builder = tar::Builder::new(encoder);
+    for (name, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o600);
+        header.set_mtime(mtime);
+        header.set_cksum();
+        builder.append_data(&mut header, name, content.as_slice())?;
+    }
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+fn read_archive(path: &Path) -> io::Result<BTreeMap<String, Vec<u8>>> {
+    let mut archive = tar::Archive::new(zstd::Decoder::new(File::open(path)?)?);
+    let mut files = BTreeMap::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        files.insert(name, content);
+    }
+    Ok(files)
+}
+
+pub async fn handle_backup_create(
+    output_file: Option<PathBuf>,
+    only: &[BackupSection],
+    include_secrets: bool,
+) -> anyhow::Result<()> {
+    let server_url = load_credentials()?.server_url;
+    let api_client = build_client()?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let output_file = output_file.unwrap_or_else(|| {
+        let stamp = Utc
+            .timestamp_opt(now as i64, 0)
+            .single()
+            .map(|t| t.format("%Y%m%d-%H%M%S").to_string())
+            .unwrap_or_else(|| now.to_string());
+        PathBuf::from(format!("astrbot-backup-{}.tar.zst", stamp))
+    });
+
+    let sections = selected_sections(only);
+    let mut files = Vec::new();
+    for section in &sections {
+        iprintln!("Backing up {}...", section.name());
+        let mut value = collect(&api_client, *section).await?;
+        if !include_secrets {
+            redact(&mut value);
+        }
+        files.push((file_name(*section), serde_json::to_vec_pretty(&value)?));
+    }
+
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        created_at: now as i64,
+        cli_version: env!("CARGO_PKG_VERSION").to_string(),
+        server_url,
+        astrbot_version: api_client.check_update().await.ok().map(|(s, _)| s.version),
+        sections: sections.iter().map(|s| s.name().to_string()).collect(),
+        secrets_included: include_secrets,
+    };
+    files.insert(
+        0,
+        (
+            MANIFEST_FILE.to_string(),
+            serde_json::to_vec_pretty(&manifest)?,
+        ),
+    );
+    write_archive(&output_file, &files, now)
+        .with_context(|| format!("Failed to write {}", output_file.display()))?;
+
+    if include_secrets {
+        iprintln!("The archive contains secrets, keep it somewhere safe");
+    }
+    if output::is_json() {
+        return output::print_json(&json!({
+            "file": output_file,
+            "manifest": manifest,
+        }));
+    }
+    output::print_message(&format!(
+        "Backup of {} sections written to {}",
+        sections.len(),
+        output_file.display()
+    ));
+    Ok(())
+}
+
+/// A change restoring one item
+enum Op {
+    CoreConfig(Value),
+    CreateProvider(Value),
+    UpdateProvider(String, Value),
+    CreatePlatform(Value),
+    UpdatePlatform(String, Value),
+    CreatePersona(Persona),
+    UpdatePersona(Persona),
+    AddMcp(String, bool, Map<String, Value>),
+    UpdateMcp(String, bool, Map<String, Value>),
+    InstallPlugin(String),
+    TogglePlugin(String, bool),
+    PluginConfig(String, Value),
+    CreateKb(NewKnowledgeBase),
+}
+
+struct Change {
+    section: BackupSection,
+    /// What the change does, e.g. `create provider openai`
+    summary: String,
+    op: Op,
+}
+
+#[derive(Default)]
+pub struct Plan {
+    changes: Vec<Change>,
+    unchanged: usize,
+    /// Skipped items and secrets to fill in by hand
+    notes: Vec<String>,
+    /// Plugins the plan installs, their configs can only be set afterwards
+    installs: Vec<String>,
+}
+
+impl Plan {
+    fn push(&mut self, section: BackupSection, summary: String, op: Op) {
+        self.changes.push(Change {
+            section,
+            summary,
+            op,
+        });
+    }
+
+    fn note_missing(&mut self, item: &str, missing: Vec<String>) {
+        if !missing.is_empty() {
+            self.notes.push(format!(
+                "{}: set {} after restoring, the backup has no secrets",
+                item,
+                missing.join(", ")
+            ));
+        }
+    }
+}
+
+fn parse_section<T: for<'de> Deserialize<'de>>(
+    section: BackupSection,
+    value: Value,
+) -> anyhow::Result<T> {
+    serde_json::from_value(value)
+        .map_err(|e| CliError::Validation(format!("Invalid {} data: {}", section.name(), e)).into())
+}
+
+fn config_id(config: &Value) -> &str {
+    config.get("id").and_then(Value::as_str).unwrap_or_default()
+}
+
+/// Plan providers or platforms, which are both configs keyed by `id`
+fn plan_by_id(
+    plan: &mut Plan,
+    section: BackupSection,
+    kind: &str,
+    archived: Vec<Value>,
+    current: &[Value],
+) {
+    for mut item in archived {
+        let id = config_id(&item).to_string();
+        if id.is_empty() {
+            plan.notes.push(format!("{} without id skipped", kind));
+            continue;
+        }
+        let existing = current.iter().find(|c| config_id(c) == id);
+        let mut missing = Vec::new();
+        fill_redacted(&mut item, existing, "", &mut missing);
+        plan.note_missing(&format!("{} {}", kind, id), missing);
+        let summary = match existing {
+            Some(existing) if *existing == item => {
+                plan.unchanged += 1;
+                continue;
+            }
+            Some(_) => format!("update {} {}", kind, id),
+            None => format!("create {} {}", kind, id),
+        };
+        let op = match (section, existing.is_some()) {
+            (BackupSection::Providers, true) => Op::UpdateProvider(id, item),
+            (BackupSection::Providers, false) => Op::CreateProvider(item),
+            (_, true) => Op::UpdatePlatform(id, item),
+            (_, false) => Op::CreatePlatform(item),
+        };
+        plan.push(section, summary, op);
+    }
+}
+
+async fn plan_section(
+    api_client: &ApiClient,
+    plan: &mut Plan,
+    section: BackupSection,
+    archived: Value,
+) -> anyhow::Result<()> {
+    match section {
+        BackupSection::CoreConfig => {
+            let mut config: Value = parse_section(section, archived)?;
+            let current = api_client.get_core_config(CONF_ID).await?.config;
+            let mut missing = Vec::new();
+            fill_redacted(&mut config, Some(&current), "", &mut missing);
+            plan.note_missing("core config", missing);
+            // The target keeps its own instance keys, archives written
+            // before they were left out may still carry them
+            if let (Value::Object(config), Value::Object(current)) = (&mut config, &current) {
+                for key in INSTANCE_KEYS {
+                    match current.get(key) {
+                        Some(value) => config.insert(key.to_string(), value.clone()),
+                        None => config.remove(key),
+                    };
+                }
+            }
+            if config == current {
+                plan.unchanged += 1;
+            } else {
+                plan.push(
+                    section,
+                    "update core config".to_string(),
+                    Op::CoreConfig(config),
+                );
+            }
+        }
+        BackupSection::Providers => {
+            let current = api_client.get_providers(&PROVIDER_TYPES.join(",")).await?;
+            plan_by_id(
+                plan,
+                section,
+                "provider",
+                parse_section(section, archived)?,
+                &current,
+            );
+        }
+        BackupSection::Platforms => {
+            let current = api_client.get_platforms().await?;
+            plan_by_id(
+                plan,
+                section,
+                "platform",
+                parse_section(section, archived)?,
+                &current,
+            );
+        }
+        BackupSection::Personas => {
+            let current = api_client.get_personas().await?;
+            let archived: Vec<Persona> = parse_section(section, archived)?;
+            for persona in archived {
+                let id = persona.persona_id.clone();
+                match current.iter().find(|p| p.persona_id == id) {
+                    Some(existing) if *existing == persona => plan.unchanged += 1,
+                    Some(_) => plan.push(
+                        section,
+                        format!("update persona {}", id),
+                        Op::UpdatePersona(persona),
+                    ),
+                    None => plan.push(
+                        section,
+                        format!("create persona {}", id),
+                        Op::CreatePersona(persona),
+                    ),
+                }
+            }
+        }
+        BackupSection::Mcp => {
+            let current = api_client.get_mcp_servers().await?;
+            let archived: Vec<Value> = parse_section(section, archived)?;
+            for server in archived {
+                let name = server_name(&server).to_string();
+                let active = is_active(&server);
+                let mut config = Value::Object(server_config(&server));
+                let existing = current.iter().find(|s| server_name(s) == name);
+                let existing_config = existing.map(|s| Value::Object(server_config(s)));
+                let mut missing = Vec::new();
+                fill_redacted(&mut config, existing_config.as_ref(), "", &mut missing);
+                plan.note_missing(&format!("MCP server {}", name), missing);
+                let Value::Object(config) = config else {
+                    continue;
+                };
+                match existing {
+                    Some(existing)
+                        if is_active(existing) == active && server_config(existing) == config =>
+                    {
+                        plan.unchanged += 1
+                    }
+                    Some(_) => plan.push(
+                        section,
+                        format!("update MCP server {}", name),
+                        Op::UpdateMcp(name, active, config),
+                    ),
+                    None => plan.push(
+                        section,
+                        format!("add MCP server {}", name),
+                        Op::AddMcp(name, active, config),
+                    ),
+                }
+            }
+        }
+        BackupSection::Plugins => {
+            let current = api_client.get_plugins().await?;
+            let archived: Vec<PluginEntry> = parse_section(section, archived)?;
+            for plugin in archived {
+                match current.iter().find(|p| p.name == plugin.name) {
+                    None if plugin.repo.is_empty() => plan.notes.push(format!(
+                        "plugin {}: no repository URL in the backup, install it by hand",
+                        plugin.name
+                    )),
+                    None => {
+                        plan.push(
+                            section,
+                            format!("install plugin {} from {}", plugin.name, plugin.repo),
+                            Op::InstallPlugin(plugin.repo.clone()),
+                        );
+                        plan.installs.push(plugin.name.clone());
+                        if !plugin.activated {
+                            plan.push(
+                                section,
+                                format!("disable plugin {}", plugin.name),
+                                Op::TogglePlugin(plugin.name, false),
+                            );
+                        }
+                    }
+                    Some(installed) => {
+                        if installed.version != plugin.version {
+                            plan.notes.push(format!(
+                                "plugin {}: version {} installed, {} expected",
+                                plugin.name, installed.version, plugin.version
+                            ));
+                        }
+                        if installed.activated == plugin.activated {
+                            plan.unchanged += 1;
+                        } else {
+                            let verb = if plugin.activated {
+                                "enable"
+                            } else {
+                                "disable"
+                            };
+                            plan.push(
+                                section,
+                                format!("{} plugin {}", verb, plugin.name),
+                                Op::TogglePlugin(plugin.name, plugin.activated),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        BackupSection::PluginConfigs => {
+            let installed: Vec<String> = api_client
+                .get_plugins()
+                .await?
+                .into_iter()
+                .map(|p| p.name)
+                .collect();
+            let archived: Map<String, Value> = parse_section(section, archived)?;
+            for (name, mut config) in archived {
+                let current = if installed.contains(&name) {
+                    Some(api_client.get_plugin_config(&name).await?.config)
+                } else if plan.installs.contains(&name) {
+                    None
+                } else {
+                    plan.notes.push(format!(
+                        "plugin config {}: the plugin is not installed, skipped",
+                        name
+                    ));
+                    continue;
+                };
+                let mut missing = Vec::new();
+                fill_redacted(&mut config, current.as_ref(), "", &mut missing);
+                plan.note_missing(&format!("plugin config {}", name), missing);
+                match current {
+                    Some(current) if current == config => plan.unchanged += 1,
+                    Some(_) => plan.push(
+                        section,
+                        format!("update plugin config {}", name),
+                        Op::PluginConfig(name, config),
+                    ),
+                    None => plan.push(
+                        section,
+                        format!("set plugin config {} after install", name),
+                        Op::PluginConfig(name, config),
+                    ),
+                }
+            }
+        }
+        BackupSection::Kb => {
+            let current = api_client.get_knowledge_bases().await?;
+            let archived: Vec<KnowledgeBase> = parse_section(section, archived)?;
+            for kb in archived {
+                if current.iter().any(|c| c.kb_name == kb.kb_name) {
+                    plan.unchanged += 1;
+                    continue;
+                }
+                let Some(embedding_provider_id) = kb.embedding_provider_id else {
+                    plan.notes.push(format!(
+                        "knowledge base {}: no embedding provider, skipped",
+                        kb.kb_name
+                    ));
+                    continue;
+                };
+                if kb.doc_count > 0 {
+                    plan.notes.push(format!(
+                        "knowledge base {}: its {} documents are not copied, upload them again",
+                        kb.kb_name, kb.doc_count
+                    ));
+                }
+                plan.push(
+                    section,
+                    format!("create knowledge base {}", kb.kb_name),
+                    Op::CreateKb(NewKnowledgeBase {
+                        kb_name: kb.kb_name,
+                        description: kb.description,
+                        embedding_provider_id,
+                        rerank_provider_id: kb.rerank_provider_id,
+                        chunk_size: None,
+                        chunk_overlap: None,
+                    }),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn apply(api_client: &ApiClient, op: &Op) -> anyhow::Result<String> {
+    match op {
+        Op::CoreConfig(config) => api_client.update_core_config(CONF_ID, config).await,
+        Op::CreateProvider(config) => api_client.create_provider(config).await,
+        Op::UpdateProvider(id, config) => api_client.update_provider(id, config).await,
+        Op::CreatePlatform(config) => api_client.create_platform(config).await,
+        Op::UpdatePlatform(id, config) => api_client.update_platform(id, config).await,
+        Op::CreatePersona(persona) => api_client.create_persona(persona).await,
+        Op::UpdatePersona(persona) => api_client.update_persona(persona).await,
+        Op::AddMcp(name, active, config) => api_client.add_mcp_server(name, *active, config).await,
+        Op::UpdateMcp(name, active, config) => {
+            api_client.update_mcp_server(name, *active, config).await
+        }
+        Op::InstallPlugin(repo) => api_client.install_remote_plugin(repo).await,
+        Op::TogglePlugin(name, on) => {
+            let action = if *on { "on" } else { "off" };
+            api_client.plugin_common_actions_request(name, action).await
+        }
+        Op::PluginConfig(name, config) => api_client.update_plugin_config(name, config).await,
+        Op::CreateKb(kb) => api_client.create_knowledge_base(kb).await,
+    }
+}
+
+pub async fn handle_backup_restore(
+    file: &Path,
+    only: &[BackupSection],
+    dry_run: bool,
+    yes: bool,
+) -> anyhow::Result<()> {
+    let files = read_archive(file).map_err(|e| {
+        CliError::Validation(format!("{} is not a backup archive: {}", file.display(), e))
+    })?;
+    let manifest: Manifest = files
+        .get(MANIFEST_FILE)
+        .ok_or_else(|| CliError::Validation(format!("{} has no manifest", file.display())))
+        .and_then(|content| {
+            serde_json::from_slice(content)
+                .map_err(|e| CliError::Validation(format!("Invalid manifest: {}", e)))
+        })?;
+    if manifest.format_version > FORMAT_VERSION {
+        anyhow::bail!(CliError::Validation(format!(
+            "Backup format {} is newer than this CLI supports ({}), upgrade astrbot-cli",
+            manifest.format_version, FORMAT_VERSION
+        )));
+    }
+    iprintln!(
+        "Backup of {} (AstrBot {}) taken {}",
+        manifest.server_url,
+        manifest.astrbot_version.as_deref().unwrap_or("unknown"),
+        strf_timestamp(manifest.created_at)?
+    );
+
+    let sections = selected_sections(only);
+    if let Some(absent) = sections
+        .iter()
+        .find(|s| !only.is_empty() && !manifest.sections.iter().any(|n| n == s.name()))
+    {
+        anyhow::bail!(CliError::Validation(format!(
+            "The backup has no {} section",
+            absent.name()
+        )));
+    }
+
+    let mut archived = Vec::new();
+    for section in sections
+        .into_iter()
+        .filter(|s| manifest.sections.iter().any(|n| n == s.name()))
+    {
+        let name = file_name(section);
+        let content = files
+            .get(&name)
+            .ok_or_else(|| CliError::Validation(format!("The backup has no {} file", name)))?;
+        let value = serde_json::from_slice(content)
+            .map_err(|e| CliError::Validation(format!("Invalid {}: {}", name, e)))?;
+        archived.push((section, value));
+    }
+
+    let api_client = build_client()?;
+    let plan = plan(&api_client, archived).await?;
+    execute(&api_client, &plan, dry_run, yes).await
+}
+
+/// Compare `sections` with the current state and list the changes bringing
+/// the instance in line with them
+pub async fn plan(
+    api_client: &ApiClient,
+    sections: Vec<(BackupSection, Value)>,
+) -> anyhow::Result<Plan> {
+    let mut plan = Plan::default();
+    for (section, value) in sections {
+        vprintln!("Comparing {}...", section.name());
+        plan_section(api_client, &mut plan, section, value).await?;
+    }
+    Ok(plan)
+}
+
+/// Print `plan`, then apply it after confirmation unless `dry_run`.
+///
+/// A failing change does not stop the others, the run fails at the end.
+pub async fn execute(
+    api_client: &ApiClient,
+    plan: &Plan,
+    dry_run: bool,
+    yes: bool,
+) -> anyhow::Result<()> {
+    if !output::is_json() {
+        for change in &plan.changes {
+            println!("{}", change.summary);
+        }
+        for note in &plan.notes {
+            println!("note: {}", note);
+        }
+    }
+    iprintln!(
+        "{} changes, {} items unchanged",
+        plan.changes.len(),
+        plan.unchanged
+    );
+
+    let mut results = Vec::new();
+    let mut failed = 0;
+    if !dry_run && !plan.changes.is_empty() {
+        if !yes && !confirm(&format!("Apply {} changes?", plan.changes.len()))? {
+            output::print_message("Aborted");
+            return Ok(());
+        }
+        for change in &plan.changes {
+            let status = match apply(api_client, &change.op).await {
+                Ok(_) => {
+                    iprintln!("done   {}", change.summary);
+                    json!({ "status": "done" })
+                }
+                Err(e) => {
+                    failed += 1;
+                    iprintln!("failed {}: {:#}", change.summary, e);
+                    json!({ "status": "failed", "error": format!("{:#}", e) })
+                }
+            };
+            results.push((change, status));
+        }
+    }
+
+    if output::is_json() {
+        let changes: Vec<Value> = if dry_run {
+            plan.changes
+                .iter()
+                .map(|c| json!({ "section": c.section.name(), "change": c.summary, "status": "planned" }))
+                .collect()
+        } else {
+            results
+                .into_iter()
+                .map(|(c, mut status)| {
+                    status["section"] = json!(c.section.name());
+                    status["change"] = json!(c.summary);
+                    status
+                })
+                .collect()
+        };
+        output::print_json(&json!({
+            "changes": changes,
+            "unchanged": plan.unchanged,
+            "notes": plan.notes,
+        }))?;
+    }
+    if failed > 0 {
+        anyhow::bail!(CliError::Api(format!(
+            "{} of {} changes failed",
+            failed,
+            plan.changes.len()
+        )));
+    }
+    Ok(())
+}
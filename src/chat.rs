@@ -0,0 +1,390 @@
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::{ApiClient, ChatEvent, ChatMessage};
+use crate::error::{self, CliError};
+use crate::utils::build_client;
+use crate::{iprintln, output, vprintln};
+
+const HELP: &str = "\
+/new                 start a new session
+/sessions            list sessions
+/session <id>        switch to a session
+/persona [id]        list personas, or switch the conversation to one
+/provider [id]       list chat providers, or use one (`default` to reset)
+/model [name]        use a model of the provider (`default` to reset)
+/image <path>        attach an image to the next message
+/help                show this help
+/quit                leave
+Other slash commands are sent to the bot, use `//` to send one of the above.";
+
+/// What the REPL keeps between messages
+struct ChatState {
+    session_id: String,
+    provider: Option<String>,
+    model: Option<String>,
+    /// Uploaded images waiting for the next message
+    images: Vec<String>,
+}
+
+/// The bot's answer to one message
+#[derive(Default, Serialize)]
+pub struct Reply {
+    session_id: String,
+    pub text: String,
+    pub images: Vec<String>,
+    audios: Vec<String>,
+    #[serde(skip)]
+    streamed: bool,
+    /// Whether the last printed text did not end with a newline
+    #[serde(skip)]
+    line_open: bool,
+}
+
+impl Reply {
+    /// Collect an event, printing it as it arrives when `echo` is set
+    fn push(&mut self, api_client: &ApiClient, event: ChatEvent, echo: bool) -> io::Result<()> {
+        let mut out = io::stdout().lock();
+        // Everything but streamed text starts on its own line
+        if echo && self.line_open && !(event.kind == "plain" && event.streaming) {
+            writeln!(out)?;
+            self.line_open = false;
+        }
+        match event.kind.as_str() {
+            "plain" if event.streaming => {
+                self.streamed = true;
+                self.text.push_str(&event.data);
+                if echo {
+                    write!(out, "{}", event.data)?;
+                    out.flush()?;
+                    self.line_open = !event.data.ends_with('\n');
+                }
+            }
+            "plain" => {
+                if !self.text.is_empty() {
+                    self.text.push('\n');
+                }
+                self.text.push_str(&event.data);
+                if echo {
+                    writeln!(out, "{}", event.data)?;
+                }
+            }
+            // The full text of a streamed reply, already printed
+            "complete" if self.streamed => {}
+            "complete" => {
+                self.text = event.data;
+                if echo {
+                    writeln!(out, "{}", self.text)?;
+                }
+            }
+            "break" if self.streamed => self.text.push('\n'),
+            "image" | "audio" => {
+                let url = api_client.chat_file_url(&event.data);
+                if echo {
+                    writeln!(out, "[{}] {}", event.kind, url)?;
+                }
+                if event.kind == "image" {
+                    self.images.push(url);
+                } else {
+                    self.audios.push(url);
+                }
+            }
+            _ => vprintln!("Chat event `{}`: {}", event.kind, event.data),
+        }
+        Ok(())
+    }
+}
+
+async fn upload_image(api_client: &ApiClient, path: &Path) -> anyhow::Result<String> {
+    let content = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| CliError::Validation(format!("Invalid file name {}", path.display())))?;
+    vprintln!("Uploading {}", path.display());
+    api_client.upload_chat_image(file_name, content).await
+}
+
+async fn send(
+    api_client: &ApiClient,
+    state: &mut ChatState,
+    text: &str,
+    echo: bool,
+) -> anyhow::Result<Reply> {
+    let message = ChatMessage {
+        session_id: state.session_id.clone(),
+        message: text.to_string(),
+        image_url: std::mem::take(&mut state.images),
+        audio_url: Vec::new(),
+        selected_provider: state.provider.clone(),
+        selected_model: state.model.clone(),
+    };
+    exchange(api_client, &message, echo).await
+}
+
+/// Send a message and collect the reply, printing it as it arrives when
+/// `echo` is set
+pub async fn exchange(
+    api_client: &ApiClient,
+    message: &ChatMessage,
+    echo: bool,
+) -> anyhow::Result<Reply> {
+    let mut reply = Reply {
+        session_id: message.session_id.clone(),
+        ..Default::default()
+    };
+    api_client
+        .send_chat_message(message, |event| Ok(reply.push(api_client, event, echo)?))
+        .await?;
+    if reply.line_open {
+        println!();
+    }
+    Ok(reply)
+}
+
+async fn start_session(api_client: &ApiClient, session: Option<String>) -> anyhow::Result<String> {
+    match session {
+        Some(session_id) => Ok(session_id),
+        None => {
+            let session_id = api_client.new_chat_session().await?;
+            iprintln!("Started session {}", session_id);
+            Ok(session_id)
+        }
+    }
+}
+
+pub async fn handle_chat(
+    message: Option<String>,
+    session: Option<String>,
+    images: &[PathBuf],
+    provider: Option<String>,
+    model: Option<String>,
+) -> anyhow::Result<()> {
+    // One-shot replies are read by scripts, keep info lines off stdout
+    if message.is_some() {
+        output::reserve_stdout();
+    }
+    let api_client = build_client()?;
+    let mut state = ChatState {
+        session_id: start_session(&api_client, session).await?,
+        provider,
+        model,
+        images: Vec::new(),
+    };
+    for image in images {
+        let name = upload_image(&api_client, image).await?;
+        state.images.push(name);
+    }
+
+    match message {
+        Some(message) => {
+            let message = if message == "-" {
+                let mut text = String::new();
+                io::stdin().read_to_string(&mut text)?;
+                text
+            } else {
+                message
+            };
+            let reply = send(&api_client, &mut state, &message, !output::is_json()).await?;
+            if output::is_json() {
+                output::print_json(&reply)?;
+            }
+            Ok(())
+        }
+        None => repl(&api_client, &mut state).await,
+    }
+}
+
+async fn repl(api_client: &ApiClient, state: &mut ChatState) -> anyhow::Result<()> {
+    iprintln!("Type /help for commands, /quit or Ctrl-D to leave");
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.read_line(&mut line)? == 0 {
+            println!();
+            return Ok(());
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let text = if let Some(escaped) = line.strip_prefix("//") {
+            format!("/{}", escaped)
+        } else if line.starts_with('/') {
+            let (command, arg) = line
+                .split_once(char::is_whitespace)
+                .map(|(c, a)| (c, a.trim()))
+                .unwrap_or((line, ""));
+            let result = match command {
+                "/quit" | "/exit" => return Ok(()),
+                "/help" => {
+                    println!("{}", HELP);
+                    Ok(None)
+                }
+                "/new" => new_session(api_client, state).await,
+                "/sessions" => list_sessions(api_client, state).await,
+                "/session" => switch_session(state, arg),
+                "/persona" => persona(api_client, arg).await,
+                "/provider" => select_provider(api_client, state, arg).await,
+                "/model" => select_model(state, arg),
+                "/image" => attach_image(api_client, state, arg).await,
+                _ => Ok(Some(line.to_string())),
+            };
+            // A failed slash command must not end the session
+            match result {
+                Ok(Some(text)) => text,
+                Ok(None) => continue,
+                Err(e) => {
+                    error::render(&e);
+                    continue;
+                }
+            }
+        } else {
+            line.to_string()
+        };
+
+        match send(api_client, state, &text, !output::is_json()).await {
+            Ok(reply) if output::is_json() => output::print_json(&reply)?,
+            Ok(_) => {}
+            Err(e) => {
+                error::render(&e);
+            }
+        }
+    }
+}
+
+async fn new_session(
+    api_client: &ApiClient,
+    state: &mut ChatState,
+) -> anyhow::Result<Option<String>> {
+    state.session_id = start_session(api_client, None).await?;
+    state.images.clear();
+    Ok(None)
+}
+
+async fn list_sessions(
+    api_client: &ApiClient,
+    state: &ChatState,
+) -> anyhow::Result<Option<String>> {
+    for session in api_client.get_chat_sessions().await? {
+        let marker = if session.session_id == state.session_id {
+            "*"
+        } else {
+            " "
+        };
+        let updated = match &session.updated_at {
+            Value::Null => String::new(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        println!(
+            "{} {}  {}  {}",
+            marker,
+            session.session_id,
+            session.display_name.as_deref().unwrap_or("-"),
+            updated
+        );
+    }
+    Ok(None)
+}
+
+fn switch_session(state: &mut ChatState, session_id: &str) -> anyhow::Result<Option<String>> {
+    if session_id.is_empty() {
+        anyhow::bail!(CliError::Validation("Usage: /session <id>".to_string()));
+    }
+    state.session_id = session_id.to_string();
+    iprintln!("Switched to session {}", session_id);
+    Ok(None)
+}
+
+/// List personas, or let the bot's own `/persona` command switch to one
+async fn persona(api_client: &ApiClient, persona_id: &str) -> anyhow::Result<Option<String>> {
+    let personas = api_client.get_personas().await?;
+    if persona_id.is_empty() {
+        for persona in &personas {
+            println!("  {}", persona.persona_id);
+        }
+        return Ok(None);
+    }
+    if !personas.iter().any(|p| p.persona_id == persona_id) {
+        anyhow::bail!(CliError::NotFound(format!(
+            "Persona `{}` does not exist",
+            persona_id
+        )));
+    }
+    Ok(Some(format!("/persona {}", persona_id)))
+}
+
+async fn select_provider(
+    api_client: &ApiClient,
+    state: &mut ChatState,
+    id: &str,
+) -> anyhow::Result<Option<String>> {
+    let providers = api_client.get_providers("chat_completion").await?;
+    let provider_id = |p: &Value| p.get("id").and_then(Value::as_str).map(str::to_string);
+    match id {
+        "" => {
+            for provider in &providers {
+                let id = provider_id(provider).unwrap_or_default();
+                let marker = if state.provider.as_deref() == Some(id.as_str()) {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("{} {}", marker, id);
+            }
+        }
+        "default" => {
+            state.provider = None;
+            state.model = None;
+            iprintln!("Using the default provider");
+        }
+        id => {
+            if !providers
+                .iter()
+                .any(|p| provider_id(p).as_deref() == Some(id))
+            {
+                anyhow::bail!(CliError::NotFound(format!(
+                    "Provider `{}` does not exist",
+                    id
+                )));
+            }
+            state.provider = Some(id.to_string());
+            state.model = None;
+            iprintln!("Using provider {}", id);
+        }
+    }
+    Ok(None)
+}
+
+fn select_model(state: &mut ChatState, model: &str) -> anyhow::Result<Option<String>> {
+    match model {
+        "" => println!("{}", state.model.as_deref().unwrap_or("default")),
+        "default" => state.model = None,
+        model => state.model = Some(model.to_string()),
+    }
+    Ok(None)
+}
+
+async fn attach_image(
+    api_client: &ApiClient,
+    state: &mut ChatState,
+    path: &str,
+) -> anyhow::Result<Option<String>> {
+    if path.is_empty() {
+        anyhow::bail!(CliError::Validation("Usage: /image <path>".to_string()));
+    }
+    let name = upload_image(api_client, Path::new(path)).await?;
+    state.images.push(name);
+    iprintln!("Attached {}, it will be sent with the next message", path);
+    Ok(None)
+}
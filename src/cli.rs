@@ -1,7 +1,12 @@
-use crate::iprintln;
+use crate::error::CliError;
+use crate::http;
+use crate::jsonpath;
+use crate::output;
 use crate::plugin;
+use crate::plugin_config;
 use crate::vprintln;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 use strum::AsRefStr;
 
 #[derive(Parser)]
@@ -13,6 +18,40 @@ pub struct Cli {
     #[arg(short, long, global = true, help = "Enable verbose output")]
     pub verbose: bool,
 
+    /// Connect and read timeout for HTTP requests, in seconds
+    #[arg(
+        long,
+        global = true,
+        default_value_t = 30,
+        help = "HTTP timeout in seconds"
+    )]
+    pub timeout: u64,
+
+    /// Retry count for idempotent requests on connect errors and 5xx responses
+    #[arg(
+        long,
+        global = true,
+        default_value_t = 2,
+        help = "Retries for GET requests"
+    )]
+    pub retries: u32,
+
+    /// Profile to use, stored as ~/astrbot-<profile>.json
+    #[arg(long, global = true, help = "Config profile name")]
+    pub profile: Option<String>,
+
+    /// Print results and errors as JSON
+    #[arg(long, global = true, help = "Output JSON")]
+    pub json: bool,
+
+    /// Skip TLS certificate verification
+    #[arg(
+        long,
+        global = true,
+        help = "Disable TLS certificate verification (dangerous)"
+    )]
+    pub insecure: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -32,6 +71,199 @@ pub enum Commands {
         password: String,
         #[arg(short = 's', long = "server", help = "Server URL")]
         server: String,
+        #[arg(long, help = "PEM bundle of extra CA certificates to trust")]
+        ca_cert: Option<PathBuf>,
+        #[arg(
+            long,
+            requires = "client_key",
+            help = "PEM client certificate for mTLS"
+        )]
+        client_cert: Option<PathBuf>,
+        #[arg(long, requires = "client_cert", help = "PEM private key for mTLS")]
+        client_key: Option<PathBuf>,
+        #[arg(long, help = "Proxy URL (http, https, socks5 or socks5h)")]
+        proxy: Option<String>,
+        #[arg(
+            short = 'H',
+            long = "header",
+            value_parser = http::parse_header,
+            help = "Extra header `Name: value` sent with every request, repeatable"
+        )]
+        headers: Vec<(String, String)>,
+    },
+    /// Dashboard account commands
+    Account {
+        #[command(subcommand)]
+        action: AccountCommands,
+    },
+    /// AstrBot core configuration commands
+    Config {
+        #[arg(long, default_value = "default", help = "Config file id")]
+        conf_id: String,
+        #[command(subcommand)]
+        action: ConfigCommands,
+    },
+    /// LLM provider management commands
+    Provider {
+        #[command(subcommand)]
+        action: ProviderCommands,
+    },
+    /// Messaging platform adapter commands
+    Platform {
+        #[command(subcommand)]
+        action: PlatformCommands,
+    },
+    /// Persona management commands
+    Persona {
+        #[command(subcommand)]
+        action: PersonaCommands,
+    },
+    /// Conversation browsing and export commands
+    Conversation {
+        #[command(subcommand)]
+        action: ConversationCommands,
+    },
+    /// Knowledge base commands
+    Kb {
+        #[command(subcommand)]
+        action: KbCommands,
+    },
+    /// MCP server management commands
+    Mcp {
+        #[command(subcommand)]
+        action: McpCommands,
+    },
+    /// LLM function tool commands
+    Tool {
+        #[command(subcommand)]
+        action: ToolCommands,
+    },
+    /// Chat command management across plugins
+    Command {
+        #[command(subcommand)]
+        action: CommandCommands,
+    },
+    /// Chat with the bot through the dashboard's web chat
+    Chat {
+        #[arg(
+            short,
+            long,
+            help = "Send one message, print the reply and exit; `-` reads it from stdin"
+        )]
+        message: Option<String>,
+        #[arg(long, help = "Continue an existing session instead of starting one")]
+        session: Option<String>,
+        #[arg(
+            long = "image",
+            help = "Image to attach to the first message, repeatable"
+        )]
+        images: Vec<PathBuf>,
+        #[arg(long, help = "Chat provider id to use instead of the default")]
+        provider: Option<String>,
+        #[arg(long, requires = "provider", help = "Model of the provider to use")]
+        model: Option<String>,
+    },
+    /// Scripted conversation tests
+    Test {
+        #[command(subcommand)]
+        action: TestCommands,
+    },
+    /// AstrBot core and dashboard updates
+    Update {
+        #[command(subcommand)]
+        action: UpdateCommands,
+    },
+    /// Restart AstrBot core and wait until it is back, within --wait-timeout
+    Restart {
+        #[arg(short, long, help = "Restart without confirmation")]
+        yes: bool,
+        #[arg(
+            long,
+            default_value_t = 120,
+            help = "Seconds to wait for the server to come back"
+        )]
+        wait_timeout: u64,
+    },
+    /// Back up and restore an instance through the API
+    Backup {
+        #[command(subcommand)]
+        action: BackupCommands,
+    },
+    /// Session, admin and access list management
+    Session {
+        #[command(subcommand)]
+        action: SessionCommands,
+    },
+    /// Scheduled task management
+    Cron {
+        #[command(subcommand)]
+        action: CronCommands,
+    },
+    /// Text-to-image templates and rendering
+    T2i {
+        #[command(subcommand)]
+        action: T2iCommands,
+    },
+    /// Send a message to a session through its platform
+    Send {
+        #[arg(long, help = "Unified message origin of the target session")]
+        session: String,
+        #[command(flatten)]
+        content: MessageContent,
+    },
+    /// Send a message to many groups of a platform
+    Broadcast {
+        #[arg(long, help = "Platform id")]
+        platform: String,
+        #[arg(
+            long,
+            help = "File with one group ID or unified message origin per line, `#` starts a comment"
+        )]
+        groups_file: PathBuf,
+        #[command(flatten)]
+        content: MessageContent,
+        #[arg(
+            long,
+            default_value_t = 1.0,
+            help = "Seconds to wait between two messages"
+        )]
+        interval: f64,
+        #[arg(long, help = "List the targets without sending anything")]
+        dry_run: bool,
+        #[arg(short, long, help = "Send without confirmation")]
+        yes: bool,
+    },
+    /// Compare the setup of the instances behind two profiles
+    Diff {
+        #[arg(long, help = "Profile to compare from")]
+        from: String,
+        #[arg(long, help = "Profile to compare with")]
+        to: String,
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            help = "Only compare these sections"
+        )]
+        only: Vec<BackupSection>,
+    },
+    /// Bring the instance of one profile in line with another
+    Promote {
+        #[arg(long, help = "Profile to copy from")]
+        from: String,
+        #[arg(long, help = "Profile to apply the differences to")]
+        to: String,
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            help = "Only promote these sections"
+        )]
+        only: Vec<BackupSection>,
+        #[arg(long, help = "Show what would change without changing anything")]
+        dry_run: bool,
+        #[arg(short, long, help = "Promote without confirmation")]
+        yes: bool,
     },
     /// Get astrbot stat
     Stat,
@@ -42,6 +274,919 @@ pub enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum AccountCommands {
+    /// Change the dashboard username and/or password
+    Update {
+        #[arg(long, help = "New username")]
+        new_username: Option<String>,
+        #[arg(long, help = "New password")]
+        new_password: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print the config, or the value at a dotted path
+    Get {
+        #[arg(help = "Dotted path, e.g. provider_settings.enable")]
+        path: Option<String>,
+    },
+    /// Set the value at a dotted path, converted to the current value's type
+    Set {
+        #[arg(help = "Dotted path, e.g. provider_settings.enable")]
+        path: String,
+        #[arg(help = "New value")]
+        value: String,
+    },
+    /// Edit the whole config in $EDITOR
+    Edit {
+        #[arg(short, long, help = "Apply without confirmation")]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProviderCommands {
+    /// List providers
+    List {
+        #[arg(
+            long = "capability",
+            help = "Only list providers of this capability, e.g. chat_completion"
+        )]
+        provider_type: Option<String>,
+    },
+    /// Show a provider's full config
+    Show {
+        #[arg(help = "Provider id")]
+        id: String,
+        #[arg(long, help = "Print API keys and other secrets unmasked")]
+        reveal: bool,
+    },
+    /// Add a provider
+    Add {
+        #[arg(long, help = "Provider id")]
+        id: Option<String>,
+        #[arg(
+            long = "type",
+            help = "Provider adapter, e.g. openai_chat_completion, googlegenai_chat_completion"
+        )]
+        provider_source: Option<String>,
+        #[arg(
+            long = "capability",
+            default_value = "chat_completion",
+            conflicts_with = "from_file",
+            help = "Provider capability, e.g. chat_completion, embedding"
+        )]
+        provider_type: String,
+        #[arg(long, conflicts_with = "from_file", help = "Add the provider disabled")]
+        disabled: bool,
+        #[arg(long, help = "JSON file with the provider config")]
+        from_file: Option<PathBuf>,
+        #[command(flatten)]
+        fields: ProviderFields,
+    },
+    /// Update a provider
+    Update {
+        #[arg(help = "Provider id")]
+        id: String,
+        #[command(flatten)]
+        fields: ProviderFields,
+    },
+    /// Remove a provider
+    Remove {
+        #[arg(help = "Provider id")]
+        id: String,
+        #[arg(short, long, help = "Remove without confirmation")]
+        yes: bool,
+    },
+    /// Enable a provider
+    Enable {
+        #[arg(help = "Provider id")]
+        id: String,
+    },
+    /// Disable a provider
+    Disable {
+        #[arg(help = "Provider id")]
+        id: String,
+    },
+    /// Run the server-side connectivity check of a provider
+    Test {
+        #[arg(help = "Provider id")]
+        id: String,
+    },
+    /// List the models available from a provider
+    Models {
+        #[arg(help = "Provider id")]
+        id: String,
+    },
+}
+
+/// Provider fields settable by `provider add` and `provider update`
+#[derive(Args)]
+pub struct ProviderFields {
+    #[arg(long, help = "API base URL")]
+    pub api_base: Option<String>,
+    #[arg(long, help = "Model name")]
+    pub model: Option<String>,
+    #[arg(long = "key", help = "API key, replaces existing keys, repeatable")]
+    pub keys: Vec<String>,
+    #[arg(long = "add-key", help = "API key to append, repeatable")]
+    pub add_keys: Vec<String>,
+    #[arg(
+        long,
+        value_parser = jsonpath::parse_assignment,
+        help = "Set any config field as `path=value`, repeatable"
+    )]
+    pub set: Vec<(String, String)>,
+}
+
+impl ProviderFields {
+    pub fn is_empty(&self) -> bool {
+        self.api_base.is_none()
+            && self.model.is_none()
+            && self.keys.is_empty()
+            && self.add_keys.is_empty()
+            && self.set.is_empty()
+    }
+}
+
+#[derive(Subcommand)]
+pub enum PlatformCommands {
+    /// List platform adapters
+    List,
+    /// Show an adapter's full config
+    Show {
+        #[arg(help = "Platform id")]
+        id: String,
+        #[arg(long, help = "Print secrets unmasked")]
+        reveal: bool,
+    },
+    /// Add an adapter, prompting for the fields of its template
+    Add {
+        #[arg(
+            long = "type",
+            help = "Adapter type, e.g. aiocqhttp, qq_official, telegram"
+        )]
+        adapter: Option<String>,
+        #[arg(long, help = "Platform id")]
+        id: Option<String>,
+        #[arg(
+            long,
+            value_parser = jsonpath::parse_assignment,
+            help = "Set a config field as `path=value`, repeatable"
+        )]
+        set: Vec<(String, String)>,
+        #[arg(long, help = "JSON file with the adapter config")]
+        from_file: Option<PathBuf>,
+        #[arg(long, help = "Never prompt, use template defaults")]
+        no_input: bool,
+    },
+    /// Update an adapter, prompting for each field when no --set is given
+    Update {
+        #[arg(help = "Platform id")]
+        id: String,
+        #[arg(
+            long,
+            value_parser = jsonpath::parse_assignment,
+            help = "Set a config field as `path=value`, repeatable"
+        )]
+        set: Vec<(String, String)>,
+        #[arg(long, help = "Never prompt")]
+        no_input: bool,
+    },
+    /// Remove an adapter
+    Remove {
+        #[arg(help = "Platform id")]
+        id: String,
+        #[arg(short, long, help = "Remove without confirmation")]
+        yes: bool,
+    },
+    /// Enable an adapter
+    Enable {
+        #[arg(help = "Platform id")]
+        id: String,
+    },
+    /// Disable an adapter
+    Disable {
+        #[arg(help = "Platform id")]
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PersonaCommands {
+    /// List personas
+    List,
+    /// Show a persona
+    Show {
+        #[arg(help = "Persona id")]
+        persona_id: String,
+    },
+    /// Create a persona
+    Create {
+        #[arg(help = "Persona id")]
+        persona_id: String,
+        #[command(flatten)]
+        fields: PersonaFields,
+    },
+    /// Update a persona, only the given fields change
+    Update {
+        #[arg(help = "Persona id")]
+        persona_id: String,
+        #[command(flatten)]
+        fields: PersonaFields,
+    },
+    /// Delete a persona
+    Delete {
+        #[arg(help = "Persona id")]
+        persona_id: String,
+        #[arg(short, long, help = "Delete without confirmation")]
+        yes: bool,
+    },
+    /// Export all personas as Markdown files with YAML front matter
+    Export {
+        #[arg(help = "Output directory")]
+        dir: PathBuf,
+    },
+    /// Create or update personas from a directory of Markdown/YAML files
+    Import {
+        #[arg(help = "Input directory")]
+        dir: PathBuf,
+        #[arg(long, help = "Only show what would change")]
+        dry_run: bool,
+    },
+}
+
+/// Persona fields settable by `persona create` and `persona update`
+#[derive(Args)]
+pub struct PersonaFields {
+    #[arg(long, conflicts_with = "prompt_file", help = "System prompt")]
+    pub prompt: Option<String>,
+    #[arg(long, help = "Read the system prompt from a file")]
+    pub prompt_file: Option<PathBuf>,
+    #[arg(
+        long = "dialog",
+        help = "Begin dialog message, alternating user/assistant, repeatable"
+    )]
+    pub dialogs: Vec<String>,
+    #[arg(long = "tool", help = "Allowed tool, repeatable")]
+    pub tools: Vec<String>,
+    #[arg(long, conflicts_with_all = ["tools", "no_tools"], help = "Allow all tools")]
+    pub all_tools: bool,
+    #[arg(long, conflicts_with = "tools", help = "Allow no tools")]
+    pub no_tools: bool,
+}
+
+#[derive(Subcommand)]
+pub enum ConversationCommands {
+    /// List conversations
+    List {
+        #[command(flatten)]
+        filter: ConversationFilter,
+        #[arg(
+            long,
+            default_value_t = 1,
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Page number"
+        )]
+        page: u64,
+        #[arg(
+            long,
+            default_value_t = 20,
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Conversations per page"
+        )]
+        page_size: u64,
+    },
+    /// Show a conversation as a transcript
+    Show {
+        #[arg(help = "Conversation id")]
+        cid: String,
+        #[arg(
+            long,
+            help = "Session (unified message origin), looked up when omitted"
+        )]
+        user: Option<String>,
+    },
+    /// Export the matching conversations
+    Export {
+        #[command(flatten)]
+        filter: ConversationFilter,
+        #[arg(long, value_enum, default_value_t = ExportFormat::Jsonl, help = "Export format")]
+        format: ExportFormat,
+        #[arg(short, long, help = "Output file, stdout when omitted")]
+        output_file: Option<PathBuf>,
+    },
+    /// Delete a conversation
+    Delete {
+        #[arg(help = "Conversation id")]
+        cid: String,
+        #[arg(
+            long,
+            help = "Session (unified message origin), looked up when omitted"
+        )]
+        user: Option<String>,
+        #[arg(short, long, help = "Delete without confirmation")]
+        yes: bool,
+    },
+}
+
+/// Filters shared by `conversation list` and `conversation export`
+#[derive(Args)]
+pub struct ConversationFilter {
+    #[arg(long = "platform", help = "Platform id, repeatable")]
+    pub platforms: Vec<String>,
+    #[arg(long, help = "Session/user id substring")]
+    pub user: Option<String>,
+    #[arg(long, help = "Updated on or after this date (YYYY-MM-DD)")]
+    pub since: Option<String>,
+    #[arg(long, help = "Updated on or before this date (YYYY-MM-DD)")]
+    pub until: Option<String>,
+    #[arg(long, help = "Keyword to search for")]
+    pub keyword: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Jsonl,
+    Markdown,
+}
+
+#[derive(Subcommand)]
+pub enum KbCommands {
+    /// List knowledge bases
+    List,
+    /// Show a knowledge base and its documents
+    Show {
+        #[arg(help = "Knowledge base name or id")]
+        kb: String,
+    },
+    /// Create a knowledge base
+    Create {
+        #[arg(help = "Knowledge base name")]
+        name: String,
+        #[arg(long, help = "Description")]
+        description: Option<String>,
+        #[arg(long, help = "Embedding provider id")]
+        embedding_provider: String,
+        #[arg(long, help = "Rerank provider id")]
+        rerank_provider: Option<String>,
+        #[arg(long, help = "Chunk size")]
+        chunk_size: Option<u32>,
+        #[arg(long, help = "Chunk overlap")]
+        chunk_overlap: Option<u32>,
+    },
+    /// Delete a knowledge base
+    Delete {
+        #[arg(help = "Knowledge base name or id")]
+        kb: String,
+        #[arg(short, long, help = "Delete without confirmation")]
+        yes: bool,
+    },
+    /// Upload files, directories are walked recursively
+    Upload {
+        #[arg(help = "Knowledge base name or id")]
+        kb: String,
+        #[arg(required = true, help = "Files or directories")]
+        paths: Vec<PathBuf>,
+        #[arg(
+            long = "include",
+            help = "Only upload files matching this glob, repeatable"
+        )]
+        includes: Vec<String>,
+        #[arg(long, help = "Upload even if the file is unchanged")]
+        force: bool,
+    },
+    /// Test retrieval against a knowledge base
+    Query {
+        #[arg(help = "Knowledge base name or id")]
+        kb: String,
+        #[arg(help = "Query text")]
+        text: String,
+        #[arg(long, default_value_t = 5, help = "Number of results")]
+        top_k: u32,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum McpCommands {
+    /// List MCP servers
+    List {
+        #[arg(long, help = "Also list the tools each server exposes")]
+        tools: bool,
+    },
+    /// Add a server, or import servers from an `mcpServers` JSON file
+    Add {
+        #[arg(help = "Server name, optional with --from-file to import a single entry")]
+        name: Option<String>,
+        #[command(flatten)]
+        fields: McpServerFields,
+        #[arg(long, help = "Add the server disabled")]
+        disabled: bool,
+        #[arg(
+            long,
+            conflicts_with_all = ["command", "url"],
+            help = "Claude Desktop style JSON file with an `mcpServers` object"
+        )]
+        from_file: Option<PathBuf>,
+    },
+    /// Update a server's command or URL
+    Update {
+        #[arg(help = "Server name")]
+        name: String,
+        #[command(flatten)]
+        fields: McpServerFields,
+    },
+    /// Remove a server
+    Remove {
+        #[arg(help = "Server name")]
+        name: String,
+        #[arg(short, long, help = "Remove without confirmation")]
+        yes: bool,
+    },
+    /// Enable a server
+    Enable {
+        #[arg(help = "Server name")]
+        name: String,
+    },
+    /// Disable a server
+    Disable {
+        #[arg(help = "Server name")]
+        name: String,
+    },
+    /// Check that a server can be connected to
+    Test {
+        #[arg(help = "Server name")]
+        name: String,
+    },
+}
+
+#[derive(Args)]
+pub struct McpServerFields {
+    #[arg(long, conflicts_with = "url", help = "Command starting a stdio server")]
+    pub command: Option<String>,
+    #[arg(
+        long = "arg",
+        allow_hyphen_values = true,
+        help = "Argument of the command, repeatable"
+    )]
+    pub args: Vec<String>,
+    #[arg(
+        long,
+        value_parser = parse_env,
+        help = "Environment variable as `KEY=VALUE`, repeatable"
+    )]
+    pub env: Vec<(String, String)>,
+    #[arg(long, help = "URL of an SSE or streamable HTTP server")]
+    pub url: Option<String>,
+    #[arg(long, value_enum, help = "Transport used with --url [default: sse]")]
+    pub transport: Option<McpTransport>,
+    #[arg(
+        short = 'H',
+        long = "header",
+        value_parser = http::parse_header,
+        help = "HTTP header sent to the server as `Name: value`, repeatable"
+    )]
+    pub headers: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum McpTransport {
+    Sse,
+    StreamableHttp,
+}
+
+impl McpTransport {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            McpTransport::Sse => "sse",
+            McpTransport::StreamableHttp => "streamable_http",
+        }
+    }
+}
+
+/// Parse a `KEY=VALUE` environment variable argument
+fn parse_env(arg: &str) -> Result<(String, String), String> {
+    let (key, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("invalid variable `{}`, expected `KEY=VALUE`", arg))?;
+    if key.is_empty() {
+        return Err(format!("invalid variable `{}`, the name is empty", arg));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+#[derive(Subcommand)]
+pub enum ToolCommands {
+    /// List the function tools from plugins and MCP servers
+    List,
+    /// Show a tool with its parameter schema
+    Show {
+        #[arg(help = "Tool name")]
+        name: String,
+    },
+    /// Enable a tool
+    On {
+        #[arg(help = "Tool name")]
+        name: String,
+    },
+    /// Disable a tool
+    Off {
+        #[arg(help = "Tool name")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CommandCommands {
+    /// List the commands registered by all plugins
+    List {
+        #[arg(long, help = "Only list the commands of this plugin")]
+        plugin: Option<String>,
+    },
+    /// Show triggers shared by several enabled commands
+    Conflicts,
+    /// Enable a command
+    On {
+        #[arg(help = "Command trigger or full handler name")]
+        command: String,
+        #[arg(
+            long,
+            help = "Plugin owning the command, when the trigger is ambiguous"
+        )]
+        plugin: Option<String>,
+    },
+    /// Disable a command
+    Off {
+        #[arg(help = "Command trigger or full handler name")]
+        command: String,
+        #[arg(
+            long,
+            help = "Plugin owning the command, when the trigger is ambiguous"
+        )]
+        plugin: Option<String>,
+    },
+    /// Rename a command, a sub-command keeps its group
+    Rename {
+        #[arg(help = "Command trigger or full handler name")]
+        command: String,
+        #[arg(help = "New name")]
+        new_name: String,
+        #[arg(
+            long = "alias",
+            conflicts_with = "no_aliases",
+            help = "Replace the aliases, repeatable"
+        )]
+        aliases: Vec<String>,
+        #[arg(long, help = "Remove all aliases")]
+        no_aliases: bool,
+        #[arg(
+            long,
+            help = "Plugin owning the command, when the trigger is ambiguous"
+        )]
+        plugin: Option<String>,
+        #[arg(
+            long,
+            help = "Rename even if the new name conflicts with another command"
+        )]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TestCommands {
+    /// Run the cases of a YAML test file through the web chat
+    Run {
+        #[arg(help = "YAML test file")]
+        file: PathBuf,
+        #[arg(short, long, default_value_t = 1, help = "Number of cases run at once")]
+        jobs: usize,
+        #[arg(long, help = "Only run the cases whose name contains this text")]
+        filter: Option<String>,
+        #[arg(long, value_enum, default_value_t = ReportFormat::Text, help = "Report format")]
+        format: ReportFormat,
+        #[arg(short, long = "output", help = "Write the report to a file")]
+        output_file: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum ReportFormat {
+    Text,
+    Tap,
+    Junit,
+}
+
+#[derive(Subcommand)]
+pub enum UpdateCommands {
+    /// Compare the running version with the latest release
+    Check,
+    /// Update the core and wait until the server is back, within --wait-timeout
+    Apply {
+        #[arg(long, help = "Release to install, e.g. v4.5.1 [default: latest]")]
+        version: Option<String>,
+        #[arg(short, long, help = "Update without confirmation")]
+        yes: bool,
+        #[arg(long, help = "Return once the update is triggered")]
+        no_wait: bool,
+        #[arg(
+            long,
+            default_value_t = 600,
+            help = "Seconds to wait for the server to come back"
+        )]
+        wait_timeout: u64,
+    },
+    /// Update the dashboard (WebUI) bundle
+    Dashboard {
+        #[arg(long, help = "Update even if the dashboard is up to date")]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BackupCommands {
+    /// Write a backup archive (tar.zst) of the instance
+    Create {
+        #[arg(
+            short,
+            long = "output",
+            help = "Archive path [default: astrbot-backup-<time>.tar.zst]"
+        )]
+        output_file: Option<PathBuf>,
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            help = "Only back up these sections"
+        )]
+        only: Vec<BackupSection>,
+        #[arg(long, help = "Keep API keys, tokens and passwords in the archive")]
+        include_secrets: bool,
+    },
+    /// Replay a backup archive onto the current instance
+    Restore {
+        #[arg(help = "Backup archive")]
+        file: PathBuf,
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            help = "Only restore these sections"
+        )]
+        only: Vec<BackupSection>,
+        #[arg(long, help = "Show what would change without changing anything")]
+        dry_run: bool,
+        #[arg(short, long, help = "Restore without confirmation")]
+        yes: bool,
+    },
+}
+
+/// Parts of an instance a backup can hold, in restore order
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum BackupSection {
+    CoreConfig,
+    Providers,
+    Platforms,
+    Personas,
+    Mcp,
+    Plugins,
+    PluginConfigs,
+    Kb,
+}
+
+impl BackupSection {
+    pub const ALL: [BackupSection; 8] = [
+        BackupSection::CoreConfig,
+        BackupSection::Providers,
+        BackupSection::Platforms,
+        BackupSection::Personas,
+        BackupSection::Mcp,
+        BackupSection::Plugins,
+        BackupSection::PluginConfigs,
+        BackupSection::Kb,
+    ];
+
+    /// Name in the manifest, the section is stored as `<name>.json`
+    pub fn name(self) -> &'static str {
+        match self {
+            BackupSection::CoreConfig => "core_config",
+            BackupSection::Providers => "providers",
+            BackupSection::Platforms => "platforms",
+            BackupSection::Personas => "personas",
+            BackupSection::Mcp => "mcp_servers",
+            BackupSection::Plugins => "plugins",
+            BackupSection::PluginConfigs => "plugin_configs",
+            BackupSection::Kb => "knowledge_bases",
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum SessionCommands {
+    /// List the sessions the bot has seen, with their provider and persona
+    List {
+        #[arg(long, help = "Only list sessions of this platform")]
+        platform: Option<String>,
+    },
+    /// Change the provider, persona or LLM switch of a session
+    Set {
+        #[arg(help = "Unified message origin, as shown by `session list`")]
+        umo: String,
+        #[arg(long, help = "Chat provider id")]
+        provider: Option<String>,
+        #[arg(long, help = "Persona id")]
+        persona: Option<String>,
+        #[arg(long, value_enum, help = "Whether the session may use the LLM")]
+        llm: Option<Toggle>,
+    },
+    /// Manage the IDs of bot admins
+    Admin {
+        #[command(subcommand)]
+        action: IdListCommands,
+    },
+    /// Manage the ID whitelist, only listed users and groups reach the bot
+    /// while it is on
+    Whitelist {
+        #[command(subcommand)]
+        action: WhitelistCommands,
+    },
+    /// Manage blacklisted sessions, which the bot ignores entirely
+    Blacklist {
+        #[command(subcommand)]
+        action: IdListCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum IdListCommands {
+    /// List the entries
+    List,
+    /// Add entries
+    Add {
+        #[arg(required = true, help = "IDs to add")]
+        ids: Vec<String>,
+    },
+    /// Remove entries
+    Remove {
+        #[arg(required = true, help = "IDs to remove")]
+        ids: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WhitelistCommands {
+    /// List the whitelisted IDs and whether the whitelist is on
+    List,
+    /// Add IDs, user IDs, group IDs or unified message origins
+    Add {
+        #[arg(required = true, help = "IDs to add")]
+        ids: Vec<String>,
+    },
+    /// Remove IDs
+    Remove {
+        #[arg(required = true, help = "IDs to remove")]
+        ids: Vec<String>,
+    },
+    /// Start enforcing the whitelist
+    On,
+    /// Stop enforcing the whitelist
+    Off,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum Toggle {
+    On,
+    Off,
+}
+
+impl Toggle {
+    pub fn is_on(self) -> bool {
+        self == Toggle::On
+    }
+}
+
+#[derive(Subcommand)]
+pub enum CronCommands {
+    /// List the scheduled jobs
+    List,
+    /// Show a job with its next runs
+    Show {
+        #[arg(help = "Job id or name")]
+        job: String,
+    },
+    /// Schedule the bot to act in a session
+    Add {
+        #[arg(help = "Job name")]
+        name: String,
+        #[arg(
+            long = "cron",
+            help = "Five-field cron expression, e.g. \"0 9 * * 1-5\""
+        )]
+        expression: String,
+        #[arg(long, help = "Unified message origin of the session to act in")]
+        session: String,
+        #[arg(long, help = "Instruction the bot follows when the job runs")]
+        message: String,
+        #[arg(long, help = "IANA time zone of the expression, e.g. Asia/Shanghai")]
+        timezone: Option<String>,
+        #[arg(long, help = "Job description")]
+        description: Option<String>,
+        #[arg(long, help = "Create the job paused")]
+        paused: bool,
+        #[arg(
+            long,
+            help = "Validate and preview the schedule without creating the job"
+        )]
+        dry_run: bool,
+    },
+    /// Remove a job
+    Remove {
+        #[arg(help = "Job id or name")]
+        job: String,
+        #[arg(short, long, help = "Remove without confirmation")]
+        yes: bool,
+    },
+    /// Stop running a job on schedule
+    Pause {
+        #[arg(help = "Job id or name")]
+        job: String,
+    },
+    /// Run a paused job on schedule again
+    Resume {
+        #[arg(help = "Job id or name")]
+        job: String,
+    },
+    /// Run a job once right away
+    RunNow {
+        #[arg(help = "Job id or name")]
+        job: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum T2iCommands {
+    /// Manage the HTML templates on the server
+    Template {
+        #[command(subcommand)]
+        action: T2iTemplateCommands,
+    },
+    /// Render Markdown text to an image with a template
+    Render {
+        #[arg(
+            long,
+            help = "Local HTML file, or the name of a template on the server"
+        )]
+        template: String,
+        #[arg(long, help = "Markdown file to render, `-` reads stdin")]
+        text: PathBuf,
+        #[arg(short, long, help = "Output image, .png or .jpg")]
+        output_file: PathBuf,
+        #[arg(
+            long,
+            help = "Text-to-image service, the server's `t2i_endpoint` when omitted"
+        )]
+        endpoint: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum T2iTemplateCommands {
+    /// List the templates and which one is active
+    List,
+    /// Download every template as `<name>.html`
+    Pull {
+        #[arg(help = "Output directory")]
+        dir: PathBuf,
+    },
+    /// Create or update templates from the `.html` files of a directory
+    Push {
+        #[arg(help = "Input directory")]
+        dir: PathBuf,
+        #[arg(long, help = "Only show what would change")]
+        dry_run: bool,
+    },
+    /// Make a template the one used for rendering
+    SetActive {
+        #[arg(help = "Template name")]
+        name: String,
+    },
+    /// Restore the built-in template and make it active
+    Reset {
+        #[arg(short, long, help = "Reset without confirmation")]
+        yes: bool,
+    },
+}
+
+/// Content of a message sent by `send` and `broadcast`
+#[derive(Args)]
+pub struct MessageContent {
+    #[arg(long, help = "Message text, `-` reads it from stdin")]
+    pub text: Option<String>,
+    #[arg(long, help = "Image to attach")]
+    pub image: Option<PathBuf>,
+}
+
 #[derive(Subcommand, AsRefStr)]
 pub enum LogCommands {
     /// Get astrbot live log
@@ -89,6 +1234,45 @@ pub enum PluginCommands {
         #[arg(help = "Plugin name to uninstall")]
         plugin_name: String,
     },
+    /// Manage a plugin's configuration
+    Config {
+        #[arg(help = "Plugin name")]
+        plugin_name: String,
+        #[command(subcommand)]
+        action: PluginConfigCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PluginConfigCommands {
+    /// Print the config with schema descriptions, or the value at a dotted path
+    Get {
+        #[arg(help = "Dotted path")]
+        path: Option<String>,
+    },
+    /// Set one value, or merge a JSON file into the config
+    Set {
+        #[arg(help = "Dotted path", required_unless_present = "from_file")]
+        path: Option<String>,
+        #[arg(help = "New value", requires = "path")]
+        value: Option<String>,
+        #[arg(long, conflicts_with = "path", help = "JSON file with values to merge")]
+        from_file: Option<PathBuf>,
+        #[arg(short, long, help = "Apply without confirmation")]
+        yes: bool,
+    },
+    /// Edit the config in $EDITOR
+    Edit {
+        #[arg(short, long, help = "Apply without confirmation")]
+        yes: bool,
+    },
+    /// Reset the config, or one dotted path, to the schema defaults
+    Reset {
+        #[arg(help = "Dotted path")]
+        path: Option<String>,
+        #[arg(short, long, help = "Apply without confirmation")]
+        yes: bool,
+    },
 }
 
 impl PluginCommands {
@@ -103,52 +1287,32 @@ impl PluginCommands {
     }
 }
 
-pub async fn handle_plugin_command(action: PluginCommands) {
+pub async fn handle_plugin_command(action: PluginCommands) -> anyhow::Result<()> {
     match action {
         PluginCommands::Get => {
-            let plugings = super::plugin::handle_plugin_get().await;
-            match plugings {
-                Ok(plugings) => {
-                    plugings.iter().for_each(|pl| pl.pretty_print());
-                }
-                Err(err) => {
-                    eprintln!("Error: {}", err);
-                    std::process::exit(1);
-                }
+            let plugins = super::plugin::handle_plugin_get().await?;
+            if output::is_json() {
+                output::print_json(&plugins)?;
+            } else {
+                plugins.iter().for_each(|pl| pl.pretty_print());
             }
         }
         PluginCommands::Install {
             from_local,
             from_git,
         } => {
-            if from_local {
+            let ret = if from_local {
                 vprintln!("Installing plugin from current path");
-                let ret = plugin::handle_plugin_install_from_local().await;
-                match ret {
-                    Ok(r) => {
-                        iprintln!("{}", r);
-                    }
-                    Err(err) => {
-                        eprintln!("Error: {}", err);
-                        std::process::exit(1);
-                    }
-                }
+                plugin::handle_plugin_install_from_local().await?
             } else if let Some(git_url) = from_git {
                 vprintln!("Installing plugin from git repository: {}", git_url);
-                let ret = plugin::handle_plugin_install_from_github(&git_url).await;
-                match ret {
-                    Ok(r) => {
-                        iprintln!("{}", r);
-                    }
-                    Err(err) => {
-                        eprintln!("Error: {}", err);
-                        std::process::exit(1);
-                    }
-                }
+                plugin::handle_plugin_install_from_github(&git_url).await?
             } else {
-                eprintln!("Error: Please specify either --from-local or --from-git option");
-                std::process::exit(1);
-            }
+                anyhow::bail!(CliError::Validation(
+                    "Please specify either --from-local or --from-git option".to_string()
+                ));
+            };
+            output::print_message(&ret);
         }
         cmd @ (PluginCommands::Off { .. }
         | PluginCommands::Reload { .. }
@@ -157,16 +1321,38 @@ pub async fn handle_plugin_command(action: PluginCommands) {
             let action = cmd.as_ref().to_ascii_lowercase();
             let plugin_name = cmd.plugin_name().unwrap();
             vprintln!("{} plugin: {}", action, plugin_name);
-            let ret = plugin::handle_plugin_common_actions(plugin_name, action.as_str()).await;
-            match ret {
-                Ok(r) => {
-                    iprintln!("{}", r);
-                }
-                Err(err) => {
-                    eprintln!("Error: {}", err);
-                    std::process::exit(1);
-                }
-            }
+            let ret = plugin::handle_plugin_common_actions(plugin_name, action.as_str()).await?;
+            output::print_message(&ret);
         }
+        PluginCommands::Config {
+            plugin_name,
+            action,
+        } => match action {
+            PluginConfigCommands::Get { path } => {
+                plugin_config::handle_plugin_config_get(&plugin_name, path).await?
+            }
+            PluginConfigCommands::Set {
+                path,
+                value,
+                from_file,
+                yes,
+            } => {
+                plugin_config::handle_plugin_config_set(
+                    &plugin_name,
+                    path,
+                    value,
+                    from_file.as_deref(),
+                    yes,
+                )
+                .await?
+            }
+            PluginConfigCommands::Edit { yes } => {
+                plugin_config::handle_plugin_config_edit(&plugin_name, yes).await?
+            }
+            PluginConfigCommands::Reset { path, yes } => {
+                plugin_config::handle_plugin_config_reset(&plugin_name, path, yes).await?
+            }
+        },
     }
+    Ok(())
 }
@@ -0,0 +1,294 @@
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::Context;
+
+use crate::api::{ApiClient, Command};
+use crate::error::CliError;
+use crate::utils::build_client;
+use crate::{iprintln, output, vprintln};
+
+/// Handler kinds of the plugin list that are chat commands
+const COMMAND_KINDS: [(&str, &str); 2] = [("指令", "command"), ("指令组", "group")];
+
+/// Put sub-commands next to their groups, each handler once
+fn flatten(commands: Vec<Command>, seen: &mut HashSet<String>, out: &mut Vec<Command>) {
+    for mut command in commands {
+        let subs = std::mem::take(&mut command.sub_commands);
+        if seen.insert(command.handler_full_name.clone()) {
+            out.push(command);
+        }
+        flatten(subs, seen, out);
+    }
+}
+
+/// Build the command list from the handlers in the plugin list, for servers
+/// without the command management API. Aliases and permissions are unknown.
+async fn commands_from_plugins(api_client: &ApiClient) -> anyhow::Result<Vec<Command>> {
+    let mut commands = Vec::new();
+    for plugin in api_client.get_plugins().await? {
+        for handler in &plugin.handlers {
+            let Some((_, kind)) = COMMAND_KINDS.iter().find(|(k, _)| *k == handler.kind) else {
+                continue;
+            };
+            commands.push(Command {
+                handler_full_name: handler.handler_full_name.clone(),
+                handler_name: handler.handler_name.clone(),
+                plugin: plugin.name.clone(),
+                description: handler.desc.clone(),
+                kind: kind.to_string(),
+                effective_command: handler.cmd.clone(),
+                aliases: Vec::new(),
+                permission: String::new(),
+                enabled: plugin.activated,
+                sub_commands: Vec::new(),
+            });
+        }
+    }
+    Ok(commands)
+}
+
+async fn fetch_commands(api_client: &ApiClient) -> anyhow::Result<Vec<Command>> {
+    match api_client.get_commands().await {
+        Ok(commands) => {
+            let mut out = Vec::new();
+            flatten(commands, &mut HashSet::new(), &mut out);
+            Ok(out)
+        }
+        Err(e) if matches!(e.downcast_ref(), Some(CliError::NotFound(_))) => {
+            vprintln!("Command API unavailable, using plugin handlers: {:#}", e);
+            commands_from_plugins(api_client).await
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Commands that can be changed need the server's command management API
+async fn fetch_managed_commands(api_client: &ApiClient) -> anyhow::Result<Vec<Command>> {
+    let commands = api_client
+        .get_commands()
+        .await
+        .context("The server does not support command management")?;
+    let mut out = Vec::new();
+    flatten(commands, &mut HashSet::new(), &mut out);
+    Ok(out)
+}
+
+/// The group part of a command's trigger, empty for a top level command
+fn parent_of(command: &Command) -> &str {
+    command
+        .effective_command
+        .trim()
+        .rsplit_once(char::is_whitespace)
+        .map(|(parent, _)| parent.trim_end())
+        .unwrap_or_default()
+}
+
+fn with_parent(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} {}", parent, name)
+    }
+}
+
+/// Every text that triggers a command: its full name and its aliases
+fn triggers(command: &Command) -> Vec<String> {
+    let parent = parent_of(command);
+    std::iter::once(command.effective_command.trim().to_string())
+        .chain(
+            command
+                .aliases
+                .iter()
+                .map(|a| with_parent(parent, a.trim())),
+        )
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Triggers shared by more than one enabled command
+fn find_conflicts(commands: &[Command]) -> BTreeMap<String, Vec<&Command>> {
+    let mut by_trigger: BTreeMap<String, Vec<&Command>> = BTreeMap::new();
+    for command in commands.iter().filter(|c| c.enabled) {
+        for trigger in triggers(command) {
+            let owners = by_trigger.entry(trigger).or_default();
+            if !owners
+                .iter()
+                .any(|c| c.handler_full_name == command.handler_full_name)
+            {
+                owners.push(command);
+            }
+        }
+    }
+    by_trigger.retain(|_, owners| owners.len() > 1);
+    by_trigger
+}
+
+/// Find a command by handler name or trigger, optionally within a plugin
+fn resolve<'a>(
+    commands: &'a [Command],
+    target: &str,
+    plugin: Option<&str>,
+) -> anyhow::Result<&'a Command> {
+    if let Some(command) = commands.iter().find(|c| c.handler_full_name == target) {
+        return Ok(command);
+    }
+    let target = target.split_whitespace().collect::<Vec<_>>().join(" ");
+    let matches: Vec<&Command> = commands
+        .iter()
+        .filter(|c| plugin.is_none_or(|p| c.plugin == p))
+        .filter(|c| triggers(c).contains(&target))
+        .collect();
+    match matches.as_slice() {
+        [] => anyhow::bail!(CliError::NotFound(format!(
+            "Command `{}` does not exist",
+            target
+        ))),
+        [command] => Ok(command),
+        _ => {
+            let candidates: Vec<String> = matches
+                .iter()
+                .map(|c| format!("{} ({})", c.handler_full_name, c.plugin))
+                .collect();
+            anyhow::bail!(CliError::Validation(format!(
+                "Command `{}` is ambiguous, use --plugin or one of: {}",
+                target,
+                candidates.join(", ")
+            )))
+        }
+    }
+}
+
+fn pretty_print(command: &Command) {
+    println!("Command: {}", command.effective_command);
+    if !command.aliases.is_empty() {
+        println!("Aliases: {}", command.aliases.join(", "));
+    }
+    println!("Plugin: {}", command.plugin);
+    println!("Handler: {}", command.handler_full_name);
+    println!("Type: {}", command.kind);
+    if !command.permission.is_empty() {
+        println!("Permission: {}", command.permission);
+    }
+    println!("Enabled: {}", command.enabled);
+    if !command.description.is_empty() {
+        println!("Description: {}", command.description);
+    }
+    println!()
+}
+
+pub async fn handle_command_list(plugin: Option<String>) -> anyhow::Result<()> {
+    iprintln!("Fetching command list...");
+    let api_client = build_client()?;
+    let mut commands = fetch_commands(&api_client).await?;
+    if let Some(plugin) = plugin {
+        commands.retain(|c| c.plugin == plugin);
+    }
+
+    if output::is_json() {
+        output::print_json(&commands)?;
+    } else {
+        commands.iter().for_each(pretty_print);
+    }
+    Ok(())
+}
+
+pub async fn handle_command_conflicts() -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let commands = fetch_commands(&api_client).await?;
+    let conflicts = find_conflicts(&commands);
+
+    if output::is_json() {
+        let report: Vec<serde_json::Value> = conflicts
+            .iter()
+            .map(|(trigger, owners)| {
+                serde_json::json!({
+                    "trigger": trigger,
+                    "handlers": owners
+                        .iter()
+                        .map(|c| serde_json::json!({
+                            "plugin": c.plugin,
+                            "handler_full_name": c.handler_full_name,
+                        }))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        return output::print_json(&report);
+    }
+    if conflicts.is_empty() {
+        println!("No conflicting commands");
+        return Ok(());
+    }
+    for (trigger, owners) in &conflicts {
+        println!("Trigger: {}", trigger);
+        for command in owners {
+            println!("  - {} ({})", command.plugin, command.handler_full_name);
+        }
+        println!()
+    }
+    Ok(())
+}
+
+pub async fn handle_command_toggle(
+    target: &str,
+    plugin: Option<&str>,
+    enabled: bool,
+) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let commands = fetch_managed_commands(&api_client).await?;
+    let command = resolve(&commands, target, plugin)?;
+    let ret = api_client
+        .toggle_command(&command.handler_full_name, enabled)
+        .await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_command_rename(
+    target: &str,
+    new_name: &str,
+    aliases: Option<Vec<String>>,
+    plugin: Option<&str>,
+    force: bool,
+) -> anyhow::Result<()> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() || new_name.contains(char::is_whitespace) {
+        anyhow::bail!(CliError::Validation(
+            "The new name must be a single non-empty word".to_string()
+        ));
+    }
+    let api_client = build_client()?;
+    let commands = fetch_managed_commands(&api_client).await?;
+    let command = resolve(&commands, target, plugin)?;
+
+    // Check the renamed command against the triggers of all other commands
+    let parent = parent_of(command);
+    let mut renamed = command.clone();
+    renamed.effective_command = with_parent(parent, new_name);
+    if let Some(aliases) = &aliases {
+        renamed.aliases = aliases.clone();
+    }
+    let taken: Vec<String> = commands
+        .iter()
+        .filter(|c| c.enabled && c.handler_full_name != command.handler_full_name)
+        .flat_map(triggers)
+        .collect();
+    let mut clashes: Vec<String> = triggers(&renamed)
+        .into_iter()
+        .filter(|t| taken.contains(t))
+        .collect();
+    clashes.sort();
+    clashes.dedup();
+    if !clashes.is_empty() && !force {
+        anyhow::bail!(CliError::Validation(format!(
+            "`{}` would conflict with existing commands, pass --force to rename anyway",
+            clashes.join("`, `")
+        )));
+    }
+
+    let ret = api_client
+        .rename_command(&command.handler_full_name, new_name, aliases.as_deref())
+        .await?;
+    output::print_message(&ret);
+    Ok(())
+}
@@ -1,17 +1,47 @@
 use crate::iprintln;
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 const CONFIG_FILE: &str = "astrbot.json";
 
+/// Global active profile name, `None` means the default profile
+static PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Config {
     pub token: String,
     pub server_url: String,
     pub username: String,
+    /// PEM bundle of extra CA certificates to trust
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<PathBuf>,
+    /// PEM client certificate for mTLS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<PathBuf>,
+    /// PEM private key matching `client_cert`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<PathBuf>,
+    /// Proxy URL (http, https, socks5 or socks5h)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Extra headers sent with every request
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+/// Initialize the active profile
+pub fn init_profile(profile: Option<String>) {
+    PROFILE.set(profile).expect("Profile already initialized");
+}
+
+/// Get the active profile name
+pub fn active_profile() -> Option<&'static str> {
+    PROFILE.get().and_then(|p| p.as_deref())
 }
 
 pub struct ConfigManager;
@@ -29,9 +59,35 @@ impl ConfigManager {
         Ok(PathBuf::from(home_dir))
     }
 
-    /// Get the config file path
+    /// Get the config file path of the active profile
     fn get_config_file_path() -> anyhow::Result<PathBuf> {
-        Ok(Self::get_config_dir()?.join(CONFIG_FILE))
+        Self::get_profile_file_path(active_profile())
+    }
+
+    /// Get the config file path of a profile, `None` is the default profile
+    fn get_profile_file_path(profile: Option<&str>) -> anyhow::Result<PathBuf> {
+        let file_name = match profile {
+            None => CONFIG_FILE.to_string(),
+            Some(profile) => {
+                if profile.is_empty()
+                    || !profile
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                {
+                    anyhow::bail!(
+                        "Invalid profile name `{}`: use letters, digits, `-` or `_`",
+                        profile
+                    );
+                }
+                format!("astrbot-{}.json", profile)
+            }
+        };
+        Ok(Self::get_config_dir()?.join(file_name))
+    }
+
+    /// Get the path of a CLI state file stored next to the config files
+    pub fn get_state_file_path(file_name: &str) -> anyhow::Result<PathBuf> {
+        Ok(Self::get_config_dir()?.join(file_name))
     }
 
     /// Save credentials to config file
@@ -54,13 +110,22 @@ impl ConfigManager {
 
     /// Load credentials from config file
     pub fn load_credentials() -> anyhow::Result<Config> {
-        let config_file = Self::get_config_file_path()?;
+        Self::read_config(&Self::get_config_file_path()?)
+    }
+
+    /// Load the credentials of a profile other than the active one,
+    /// `default` names the default profile
+    pub fn load_profile(profile: &str) -> anyhow::Result<Config> {
+        let profile = Some(profile).filter(|p| *p != "default");
+        Self::read_config(&Self::get_profile_file_path(profile)?)
+    }
 
+    fn read_config(config_file: &Path) -> anyhow::Result<Config> {
         if !config_file.exists() {
             anyhow::bail!("Config file does not exist");
         }
 
-        let content = fs::read_to_string(&config_file)
+        let content = fs::read_to_string(config_file)
             .with_context(|| format!("Failed to read config file: {}", config_file.display()))?;
 
         let credentials: Config = serde_json::from_str(&content)
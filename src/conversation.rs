@@ -0,0 +1,354 @@
+use std::path::Path;
+
+use anyhow::Context;
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+
+use crate::api::{ApiClient, Conversation, ConversationQuery};
+use crate::cli::{ConversationFilter, ExportFormat};
+use crate::error::CliError;
+use crate::utils::{build_client, confirm, strf_timestamp};
+use crate::{iprintln, output, vprintln};
+
+/// Page size used when walking every page of the list
+const SCAN_PAGE_SIZE: u64 = 100;
+
+/// Parse a `YYYY-MM-DD` date into a UTC timestamp, `end_of_day` makes the
+/// bound inclusive for `--until`
+fn parse_date(date: &str, end_of_day: bool) -> anyhow::Result<i64> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+        CliError::Validation(format!("Invalid date `{}`, expected YYYY-MM-DD", date))
+    })?;
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    Ok(time.expect("valid time of day").and_utc().timestamp())
+}
+
+/// Client-side part of the filters, the rest is sent to the server
+struct LocalFilter {
+    user: Option<String>,
+    since: Option<i64>,
+    until: Option<i64>,
+}
+
+impl LocalFilter {
+    fn matches(&self, conversation: &Conversation) -> bool {
+        self.user
+            .as_deref()
+            .is_none_or(|user| conversation.user_id.contains(user))
+            && self
+                .since
+                .is_none_or(|since| conversation.updated_at >= since)
+            && self
+                .until
+                .is_none_or(|until| conversation.updated_at <= until)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.user.is_none() && self.since.is_none() && self.until.is_none()
+    }
+}
+
+fn split_filter(filter: ConversationFilter) -> anyhow::Result<(ConversationQuery, LocalFilter)> {
+    let local = LocalFilter {
+        user: filter.user,
+        since: filter
+            .since
+            .as_deref()
+            .map(|d| parse_date(d, false))
+            .transpose()?,
+        until: filter
+            .until
+            .as_deref()
+            .map(|d| parse_date(d, true))
+            .transpose()?,
+    };
+    let query = ConversationQuery {
+        platforms: filter.platforms,
+        search: filter.keyword,
+        page: 1,
+        page_size: SCAN_PAGE_SIZE,
+    };
+    Ok((query, local))
+}
+
+/// Walk every page of the list, keeping the conversations `keep` accepts
+async fn scan_conversations(
+    api_client: &ApiClient,
+    mut query: ConversationQuery,
+    mut keep: impl FnMut(&Conversation) -> bool,
+) -> anyhow::Result<Vec<Conversation>> {
+    let mut found = Vec::new();
+    loop {
+        let page = api_client.get_conversations(&query).await?;
+        vprintln!(
+            "Scanned page {}/{}",
+            query.page,
+            page.pagination.total_pages
+        );
+        found.extend(page.conversations.into_iter().filter(|c| keep(c)));
+        if query.page >= page.pagination.total_pages {
+            return Ok(found);
+        }
+        query.page += 1;
+    }
+}
+
+/// Resolve the session of a conversation id, scanning the list when unknown
+async fn resolve_user(
+    api_client: &ApiClient,
+    cid: &str,
+    user: Option<String>,
+) -> anyhow::Result<String> {
+    if let Some(user) = user {
+        return Ok(user);
+    }
+    let query = ConversationQuery {
+        page: 1,
+        page_size: SCAN_PAGE_SIZE,
+        ..Default::default()
+    };
+    scan_conversations(api_client, query, |c| c.cid == cid)
+        .await?
+        .pop()
+        .map(|c| c.user_id)
+        .ok_or_else(|| CliError::NotFound(format!("Conversation `{}` does not exist", cid)).into())
+}
+
+fn format_time(timestamp: i64) -> String {
+    strf_timestamp(timestamp).unwrap_or_else(|_| "Invalid timestamp".to_string())
+}
+
+/// Flatten a chat message's content, which is either a string or a list of parts
+fn message_text(message: &Value) -> String {
+    match message.get("content") {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .map(|part| match part.get("type").and_then(Value::as_str) {
+                Some("text") => part
+                    .get("text")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                Some(kind) => format!("[{}]", kind),
+                None => part.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        Some(Value::Null) | None => match message.get("tool_calls") {
+            Some(calls) => format!("[tool calls] {}", calls),
+            None => String::new(),
+        },
+        Some(other) => other.to_string(),
+    }
+}
+
+fn message_role(message: &Value) -> &str {
+    message
+        .get("role")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown")
+}
+
+fn print_transcript(conversation: &Conversation) {
+    println!("CID: {}", conversation.cid);
+    println!("Session: {}", conversation.user_id);
+    println!(
+        "Title: {}",
+        conversation.title.as_deref().unwrap_or_default()
+    );
+    println!("Created: {}", format_time(conversation.created_at));
+    println!("Updated: {}", format_time(conversation.updated_at));
+    println!();
+    for message in conversation.messages() {
+        println!("[{}] {}", message_role(&message), message_text(&message));
+    }
+}
+
+fn to_markdown(conversation: &Conversation) -> String {
+    let mut text = format!(
+        "# {}\n\n- CID: `{}`\n- Session: `{}`\n- Created: {}\n- Updated: {}\n\n",
+        conversation.title.as_deref().unwrap_or(&conversation.cid),
+        conversation.cid,
+        conversation.user_id,
+        format_time(conversation.created_at),
+        format_time(conversation.updated_at)
+    );
+    for message in conversation.messages() {
+        text.push_str(&format!(
+            "**{}**: {}\n\n",
+            message_role(&message),
+            message_text(&message)
+        ));
+    }
+    text
+}
+
+pub async fn handle_conversation_list(
+    filter: ConversationFilter,
+    page: u64,
+    page_size: u64,
+) -> anyhow::Result<()> {
+    iprintln!("Fetching conversation list...");
+    let api_client = build_client()?;
+    let (mut query, local) = split_filter(filter)?;
+
+    // The server cannot apply the local filters, so its pages and counts
+    // only hold without them
+    let (conversations, total, total_pages) = if local.is_empty() {
+        query.page = page;
+        query.page_size = page_size;
+        let result = api_client.get_conversations(&query).await?;
+        (
+            result.conversations,
+            result.pagination.total,
+            result.pagination.total_pages,
+        )
+    } else {
+        let matches = scan_conversations(&api_client, query, |c| local.matches(c)).await?;
+        let total = matches.len() as u64;
+        let conversations = matches
+            .into_iter()
+            .skip((page.saturating_sub(1) * page_size) as usize)
+            .take(page_size as usize)
+            .collect();
+        (conversations, total, total.div_ceil(page_size))
+    };
+
+    if output::is_json() {
+        let conversations: Vec<Value> = conversations
+            .iter()
+            .map(|c| {
+                json!({
+                    "cid": c.cid,
+                    "user_id": c.user_id,
+                    "platform_id": c.platform_id,
+                    "title": c.title,
+                    "persona_id": c.persona_id,
+                    "created_at": c.created_at,
+                    "updated_at": c.updated_at,
+                })
+            })
+            .collect();
+        return output::print_json(&json!({
+            "conversations": conversations,
+            "page": page,
+            "total_pages": total_pages,
+            "total": total,
+        }));
+    }
+
+    if total == 0 {
+        println!("No conversations found");
+        return Ok(());
+    }
+    let total_pages = total_pages.max(1);
+    if page > total_pages {
+        iprintln!("Page {} is past the last page {}", page, total_pages);
+    }
+    for conversation in &conversations {
+        println!("CID: {}", conversation.cid);
+        println!("Session: {}", conversation.user_id);
+        println!(
+            "Title: {}",
+            conversation.title.as_deref().unwrap_or_default()
+        );
+        println!("Updated: {}", format_time(conversation.updated_at));
+        println!();
+    }
+    println!(
+        "Page {}/{} ({} conversations in total)",
+        page, total_pages, total
+    );
+    Ok(())
+}
+
+pub async fn handle_conversation_show(cid: &str, user: Option<String>) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let user = resolve_user(&api_client, cid, user).await?;
+    let conversation = api_client.get_conversation(&user, cid).await?;
+    if output::is_json() {
+        output::print_json(&json!({
+            "cid": conversation.cid,
+            "user_id": conversation.user_id,
+            "title": conversation.title,
+            "created_at": conversation.created_at,
+            "updated_at": conversation.updated_at,
+            "messages": conversation.messages(),
+        }))
+    } else {
+        print_transcript(&conversation);
+        Ok(())
+    }
+}
+
+pub async fn handle_conversation_export(
+    filter: ConversationFilter,
+    format: ExportFormat,
+    output_file: Option<&Path>,
+) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let (query, local) = split_filter(filter)?;
+    let matches = scan_conversations(&api_client, query, |c| local.matches(c)).await?;
+
+    let mut text = String::new();
+    for summary in &matches {
+        let conversation = api_client
+            .get_conversation(&summary.user_id, &summary.cid)
+            .await?;
+        match format {
+            ExportFormat::Jsonl => {
+                let line = json!({
+                    "cid": conversation.cid,
+                    "user_id": conversation.user_id,
+                    "platform_id": summary.platform_id,
+                    "title": conversation.title,
+                    "created_at": conversation.created_at,
+                    "updated_at": conversation.updated_at,
+                    "messages": conversation.messages(),
+                });
+                text.push_str(&line.to_string());
+                text.push('\n');
+            }
+            ExportFormat::Markdown => {
+                text.push_str(&to_markdown(&conversation));
+                text.push_str("---\n\n");
+            }
+        }
+    }
+
+    match output_file {
+        Some(path) => {
+            tokio::fs::write(path, text)
+                .await
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            iprintln!(
+                "Exported {} conversations to {}",
+                matches.len(),
+                path.display()
+            );
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+pub async fn handle_conversation_delete(
+    cid: &str,
+    user: Option<String>,
+    yes: bool,
+) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let user = resolve_user(&api_client, cid, user).await?;
+    if !yes && !confirm(&format!("Delete conversation `{}` of {}?", cid, user))? {
+        output::print_message("Aborted");
+        return Ok(());
+    }
+    let ret = api_client.delete_conversation(&user, cid).await?;
+    output::print_message(&ret);
+    Ok(())
+}
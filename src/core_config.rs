@@ -0,0 +1,110 @@
+use serde_json::Value;
+
+use crate::error::CliError;
+use crate::utils::{build_client, edit_json, review_json_changes};
+use crate::{iprintln, jsonpath, output, schema};
+
+/// Print a config value, strings are printed raw in text mode
+fn print_value(value: &Value) -> anyhow::Result<()> {
+    match value {
+        Value::String(s) if !output::is_json() => println!("{}", s),
+        other => output::print_json(other)?,
+    }
+    Ok(())
+}
+
+/// Find the schema entry of a dotted path in the dashboard config metadata,
+/// which is grouped as `{group: {metadata: {key: {type, items: {...}}}}}`
+fn schema_entry<'a>(metadata: &'a Value, path: &str) -> Option<&'a Value> {
+    metadata
+        .as_object()?
+        .values()
+        .find_map(|group| schema::entry(group.get("metadata")?, path))
+}
+
+/// Validate the top-level keys of a config against their schema entries
+fn schema_problems(metadata: &Value, config: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    let Some(groups) = metadata.as_object() else {
+        return errors;
+    };
+    for items in groups
+        .values()
+        .filter_map(|g| g.get("metadata")?.as_object())
+    {
+        for (key, entry) in items {
+            if let Some(value) = config.get(key).filter(|v| !v.is_null()) {
+                schema::validate(entry, value, key, &mut errors);
+            }
+        }
+    }
+    errors
+}
+
+pub async fn handle_config_get(conf_id: &str, path: Option<String>) -> anyhow::Result<()> {
+    iprintln!("Fetching config...");
+    let api_client = build_client()?;
+    let core = api_client.get_core_config(conf_id).await?;
+    let path = path.unwrap_or_default();
+    let value = jsonpath::get(&core.config, &path)
+        .ok_or_else(|| CliError::NotFound(format!("Config path `{}` does not exist", path)))?;
+    print_value(value)
+}
+
+pub async fn handle_config_set(conf_id: &str, path: &str, raw_value: &str) -> anyhow::Result<()> {
+    if path.is_empty() {
+        anyhow::bail!(CliError::Validation(
+            "Config path must not be empty, use `config edit` to replace the whole config"
+                .to_string()
+        ));
+    }
+
+    let api_client = build_client()?;
+    let mut core = api_client.get_core_config(conf_id).await?;
+    // The schema type wins, the current value may have drifted from it
+    let entry = schema_entry(&core.metadata, path);
+    let placeholder = entry.and_then(schema::placeholder);
+    let current = placeholder
+        .as_ref()
+        .or(jsonpath::get(&core.config, path).filter(|v| !v.is_null()));
+    let new_value = jsonpath::coerce(raw_value, current)?;
+    if let Some(entry) = entry {
+        let mut errors = Vec::new();
+        schema::validate(entry, &new_value, path, &mut errors);
+        if !errors.is_empty() {
+            anyhow::bail!(CliError::Validation(errors.join("\n")));
+        }
+    }
+    let old_value = jsonpath::set(&mut core.config, path, new_value.clone())?;
+
+    iprintln!(
+        "{}: {} -> {}",
+        path,
+        old_value.map_or_else(|| "<unset>".to_string(), |v| v.to_string()),
+        new_value
+    );
+    let ret = api_client.update_core_config(conf_id, &core.config).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_config_edit(conf_id: &str, yes: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let core = api_client.get_core_config(conf_id).await?;
+
+    let edited = edit_json(&core.config, |edited| {
+        let mut problems = jsonpath::type_mismatches(&core.config, edited);
+        if problems.is_empty() {
+            problems = schema_problems(&core.metadata, edited);
+        }
+        problems
+    })
+    .await?;
+    if !review_json_changes(&core.config, &edited, yes)? {
+        return Ok(());
+    }
+
+    let ret = api_client.update_core_config(conf_id, &edited).await?;
+    output::print_message(&ret);
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Local, TimeZone, Utc};
+use chrono_tz::Tz;
+use croner::parser::{CronParser, Seconds, Year};
+use croner::Cron;
+use serde_json::json;
+
+use crate::api::{ApiClient, CronJob, NewCronJob};
+use crate::error::CliError;
+use crate::utils::{build_client, confirm};
+use crate::{iprintln, output};
+
+/// Number of upcoming runs shown by `show` and `add`
+const PREVIEW_RUNS: usize = 5;
+
+/// Parse a five-field crontab expression, the format the scheduler accepts
+fn parse_expression(expression: &str) -> anyhow::Result<Cron> {
+    CronParser::builder()
+        .seconds(Seconds::Disallowed)
+        .year(Year::Disallowed)
+        .build()
+        .parse(expression)
+        .map_err(|e| {
+            CliError::Validation(format!("Invalid cron expression `{}`: {}", expression, e)).into()
+        })
+}
+
+fn parse_timezone(timezone: &str) -> anyhow::Result<Tz> {
+    timezone
+        .parse()
+        .map_err(|_| CliError::Validation(format!("Unknown time zone `{}`", timezone)).into())
+}
+
+fn upcoming<Z: TimeZone + Copy>(cron: &Cron, now: DateTime<Z>) -> Vec<String>
+where
+    Z::Offset: Display,
+{
+    cron.iter_after(now)
+        .take(PREVIEW_RUNS)
+        .map(|t| t.format("%Y-%m-%d %H:%M %Z").to_string())
+        .collect()
+}
+
+/// Compute the next runs locally, in this machine's time zone when the job
+/// has none
+fn next_runs(expression: &str, timezone: Option<&str>) -> anyhow::Result<Vec<String>> {
+    let cron = parse_expression(expression)?;
+    Ok(match timezone {
+        Some(timezone) => upcoming(&cron, Utc::now().with_timezone(&parse_timezone(timezone)?)),
+        None => upcoming(&cron, Local::now()),
+    })
+}
+
+/// Find a job by id or name
+async fn find_job(api_client: &ApiClient, job: &str) -> anyhow::Result<CronJob> {
+    let jobs = api_client.get_cron_jobs().await?;
+    if let Some(found) = jobs.iter().find(|j| j.job_id == job) {
+        return Ok(found.clone());
+    }
+    let mut named: Vec<CronJob> = jobs.into_iter().filter(|j| j.name == job).collect();
+    match named.len() {
+        0 => anyhow::bail!(CliError::NotFound(format!("Job `{}` does not exist", job))),
+        1 => Ok(named.remove(0)),
+        n => anyhow::bail!(CliError::Validation(format!(
+            "{} jobs are named `{}`, use the job id",
+            n, job
+        ))),
+    }
+}
+
+fn print_job(job: &CronJob) {
+    println!("ID: {}", job.job_id);
+    println!("Name: {}", job.name);
+    if !job.description.is_empty() {
+        println!("Description: {}", job.description);
+    }
+    match (&job.cron_expression, &job.timezone) {
+        (Some(expression), Some(timezone)) => println!("Schedule: {} ({})", expression, timezone),
+        (Some(expression), None) => println!("Schedule: {}", expression),
+        (None, _) => println!("Schedule: once"),
+    }
+    if let Some(session) = job.payload["session"].as_str() {
+        println!("Session: {}", session);
+    }
+    if let Some(note) = job.payload["note"].as_str() {
+        println!("Message: {}", note);
+    }
+    println!("Status: {}", if job.enabled { "active" } else { "paused" });
+    println!(
+        "Last run: {}",
+        job.last_run_at.as_deref().unwrap_or("never")
+    );
+    println!("Next run: {}", job.next_run_time.as_deref().unwrap_or("-"));
+    if let Some(error) = job.last_error.as_deref().filter(|e| !e.is_empty()) {
+        println!("Last error: {}", error);
+    }
+}
+
+/// Runs of a job without a time zone are computed in this machine's zone,
+/// which need not be the server's
+fn print_runs(runs: &[String], timezone: Option<&str>) {
+    if timezone.is_none() {
+        iprintln!("The job has no time zone, the server may run it at other times than shown");
+        println!("Next {} runs (local time):", runs.len());
+    } else {
+        println!("Next {} runs:", runs.len());
+    }
+    for run in runs {
+        println!("  {}", run);
+    }
+}
+
+pub async fn handle_cron_list() -> anyhow::Result<()> {
+    iprintln!("Fetching scheduled jobs...");
+    let api_client = build_client()?;
+    let jobs = api_client.get_cron_jobs().await?;
+
+    if output::is_json() {
+        return output::print_json(&jobs);
+    }
+    for job in &jobs {
+        print_job(job);
+        println!();
+    }
+    Ok(())
+}
+
+pub async fn handle_cron_show(job: &str) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let job = find_job(&api_client, job).await?;
+    // The server may accept expressions this parser does not, which must not
+    // hide the job itself
+    let runs = match &job.cron_expression {
+        Some(expression) => match next_runs(expression, job.timezone.as_deref()) {
+            Ok(runs) => runs,
+            Err(e) => {
+                iprintln!("Cannot preview the schedule: {:#}", e);
+                Vec::new()
+            }
+        },
+        None => Vec::new(),
+    };
+
+    if output::is_json() {
+        let mut value = json!(job);
+        value["next_runs"] = json!(runs);
+        return output::print_json(&value);
+    }
+    print_job(&job);
+    if !runs.is_empty() {
+        print_runs(&runs, job.timezone.as_deref());
+    }
+    Ok(())
+}
+
+pub async fn handle_cron_add(job: NewCronJob, dry_run: bool) -> anyhow::Result<()> {
+    let runs = next_runs(&job.cron_expression, job.timezone.as_deref())?;
+    if dry_run {
+        if output::is_json() {
+            return output::print_json(&json!({
+                "cron_expression": job.cron_expression,
+                "timezone": job.timezone,
+                "next_runs": runs,
+            }));
+        }
+        print_runs(&runs, job.timezone.as_deref());
+        return Ok(());
+    }
+
+    let api_client = build_client()?;
+    let timezone = job.timezone.clone();
+    let job = api_client.add_cron_job(&job).await?;
+
+    if output::is_json() {
+        let mut value = json!(job);
+        value["next_runs"] = json!(runs);
+        return output::print_json(&value);
+    }
+    output::print_message(&format!("Job {} created with id {}", job.name, job.job_id));
+    print_runs(&runs, timezone.as_deref());
+    Ok(())
+}
+
+pub async fn handle_cron_remove(job: &str, yes: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let job = find_job(&api_client, job).await?;
+    if !yes && !confirm(&format!("Remove job {} ({})?", job.name, job.job_id))? {
+        output::print_message("Aborted");
+        return Ok(());
+    }
+    let ret = api_client.delete_cron_job(&job.job_id).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_cron_toggle(job: &str, enabled: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let job = find_job(&api_client, job).await?;
+    if job.enabled == enabled {
+        let state = if enabled { "active" } else { "paused" };
+        output::print_message(&format!("Job {} is already {}", job.name, state));
+        return Ok(());
+    }
+    let ret = api_client
+        .set_cron_job_enabled(&job.job_id, enabled)
+        .await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_cron_run_now(job: &str) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let job = find_job(&api_client, job).await?;
+    iprintln!("Running job {}...", job.name);
+    let ret = api_client.run_cron_job(&job.job_id).await?;
+    output::print_message(&ret);
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use serde_json::json;
+
+use crate::output;
+
+/// Typed errors surfaced to the user, each kind maps to its own exit code.
+///
+/// | Exit code | Kind                                  |
+/// |-----------|---------------------------------------|
+/// | 0         | success                               |
+/// | 1         | unexpected error                      |
+/// | 2         | invalid arguments or input            |
+/// | 3         | missing or invalid config / profile   |
+/// | 4         | network error (connect, timeout, TLS) |
+/// | 5         | authentication failed                 |
+/// | 6         | resource not found                    |
+/// | 7         | server API returned an error          |
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error("{0}")]
+    Validation(String),
+    #[error("{0}")]
+    Config(String),
+    #[error("{0}")]
+    Network(String),
+    #[error("{0}")]
+    Auth(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("API error: {0}")]
+    Api(String),
+}
+
+impl CliError {
+    /// Describe a transport failure, including its underlying causes
+    pub fn network(err: reqwest::Error) -> Self {
+        CliError::Network(format!("{:#}", anyhow::Error::from(err)))
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CliError::Validation(_) => "validation",
+            CliError::Config(_) => "config",
+            CliError::Network(_) => "network",
+            CliError::Auth(_) => "auth",
+            CliError::NotFound(_) => "not_found",
+            CliError::Api(_) => "api",
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Validation(_) => 2,
+            CliError::Config(_) => 3,
+            CliError::Network(_) => 4,
+            CliError::Auth(_) => 5,
+            CliError::NotFound(_) => 6,
+            CliError::Api(_) => 7,
+        }
+    }
+}
+
+/// Find the kind and exit code of an error by walking its cause chain
+fn classify(err: &anyhow::Error) -> (&'static str, i32) {
+    for cause in err.chain() {
+        if let Some(cli_err) = cause.downcast_ref::<CliError>() {
+            return (cli_err.kind(), cli_err.exit_code());
+        }
+        if let Some(req_err) = cause.downcast_ref::<reqwest::Error>() {
+            if req_err.is_connect() || req_err.is_timeout() || req_err.is_request() {
+                return ("network", 4);
+            }
+        }
+    }
+    ("other", 1)
+}
+
+/// Print an error in the current output mode and return its exit code
+pub fn render(err: &anyhow::Error) -> i32 {
+    let (kind, code) = classify(err);
+    if output::is_json() {
+        let body = json!({
+            "error": {
+                "kind": kind,
+                "message": format!("{:#}", err),
+                "exit_code": code,
+            }
+        });
+        eprintln!("{}", body);
+    } else {
+        eprintln!("❌ Error: {:#}", err);
+    }
+    code
+}
@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::{Once, OnceLock};
+use std::time::Duration;
+
+use anyhow::Context;
+use reqwest::{
+    Certificate, Client, ClientBuilder, Identity, Method, NoProxy, Proxy, RequestBuilder, Response,
+};
+
+use crate::config::Config;
+use crate::vprintln;
+
+/// User-Agent sent with every request
+const USER_AGENT: &str = concat!("astrbot-cli/", env!("CARGO_PKG_VERSION"));
+
+/// Base delay of the exponential retry backoff
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Global HTTP options state
+static HTTP_OPTIONS: OnceLock<HttpOptions> = OnceLock::new();
+
+/// Makes sure the `--insecure` warning is printed only once
+static INSECURE_WARNING: Once = Once::new();
+
+#[derive(Debug, Clone, Copy)]
+pub struct HttpOptions {
+    /// Connect timeout, and the longest wait for the next bytes of a response
+    pub timeout: Duration,
+    /// How many times idempotent requests are retried
+    pub retries: u32,
+    /// Skip TLS certificate and hostname verification
+    pub insecure: bool,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+            retries: 2,
+            insecure: false,
+        }
+    }
+}
+
+/// Initialize the global HTTP options
+pub fn init_http_options(options: HttpOptions) {
+    HTTP_OPTIONS
+        .set(options)
+        .expect("HTTP options already initialized");
+}
+
+/// Get the global HTTP options
+pub fn http_options() -> HttpOptions {
+    HTTP_OPTIONS.get().copied().unwrap_or_default()
+}
+
+/// Build the HTTP client shared by the API client and login,
+/// using the TLS settings of the given profile
+pub fn build_http_client(profile: &Config) -> anyhow::Result<Client> {
+    Ok(client_builder(profile)?
+        .read_timeout(http_options().timeout)
+        .build()?)
+}
+
+/// Build a client like [`build_http_client`] but without the read timeout,
+/// for event streams that stay idle for long and calls the server answers
+/// only once a long task is done
+pub fn build_untimed_http_client(profile: &Config) -> anyhow::Result<Client> {
+    Ok(client_builder(profile)?.build()?)
+}
+
+/// Build a client for third-party services, without the TLS, proxy and
+/// `--insecure` settings that belong to the AstrBot server
+pub fn build_plain_http_client() -> anyhow::Result<Client> {
+    let options = http_options();
+    Ok(Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(options.timeout)
+        .read_timeout(options.timeout)
+        .gzip(true)
+        .build()?)
+}
+
+fn client_builder(profile: &Config) -> anyhow::Result<ClientBuilder> {
+    let options = http_options();
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(options.timeout)
+        .gzip(true);
+
+    if let Some(ca_cert) = &profile.ca_cert {
+        vprintln!("CA bundle: {}", ca_cert.display());
+        let pem = fs::read(ca_cert)
+            .with_context(|| format!("Failed to read CA bundle: {}", ca_cert.display()))?;
+        let certs = Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("Invalid CA bundle: {}", ca_cert.display()))?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    match (&profile.client_cert, &profile.client_key) {
+        (Some(cert), Some(key)) => {
+            vprintln!("Client certificate: {}", cert.display());
+            let mut pem = fs::read(cert).with_context(|| {
+                format!("Failed to read client certificate: {}", cert.display())
+            })?;
+            pem.push(b'\n');
+            pem.extend(
+                fs::read(key)
+                    .with_context(|| format!("Failed to read client key: {}", key.display()))?,
+            );
+            let identity = Identity::from_pem(&pem).context("Invalid client certificate or key")?;
+            builder = builder.identity(identity);
+        }
+        (None, None) => {}
+        _ => anyhow::bail!("client_cert and client_key must be configured together"),
+    }
+
+    // Without an explicit proxy reqwest already honors HTTP(S)_PROXY/NO_PROXY
+    if let Some(proxy) = &profile.proxy {
+        let shown = mask_proxy_credentials(proxy);
+        let scheme = proxy.split("://").next().unwrap_or_default();
+        if !matches!(scheme, "http" | "https" | "socks5" | "socks5h") {
+            anyhow::bail!(
+                "Unsupported proxy `{}`: use http://, https://, socks5:// or socks5h://",
+                shown
+            );
+        }
+        vprintln!("Proxy: {}", shown);
+        let proxy = Proxy::all(proxy.as_str())
+            .with_context(|| format!("Invalid proxy URL: {}", shown))?
+            .no_proxy(NoProxy::from_env());
+        builder = builder.proxy(proxy);
+    }
+
+    if options.insecure {
+        INSECURE_WARNING.call_once(|| {
+            eprintln!("⚠️  WARNING: TLS certificate verification is DISABLED (--insecure).");
+            eprintln!("⚠️  Traffic, including your token, can be intercepted.");
+        });
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    Ok(builder)
+}
+
+/// Hide the `user:pass@` part of a proxy URL
+fn mask_proxy_credentials(proxy: &str) -> String {
+    let Some((scheme, rest)) = proxy.split_once("://") else {
+        return proxy.to_string();
+    };
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    match rest[..authority_end].rfind('@') {
+        Some(at) => format!("{}://***@{}", scheme, &rest[at + 1..]),
+        None => proxy.to_string(),
+    }
+}
+
+/// Add the profile's extra headers to a request
+pub fn apply_headers(
+    mut builder: RequestBuilder,
+    headers: &BTreeMap<String, String>,
+) -> RequestBuilder {
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    builder
+}
+
+/// Parse a `Name: value` header argument
+pub fn parse_header(arg: &str) -> Result<(String, String), String> {
+    let (name, value) = arg
+        .split_once(':')
+        .ok_or_else(|| format!("invalid header `{}`, expected `Name: value`", arg))?;
+    let name = name.trim();
+    reqwest::header::HeaderName::from_bytes(name.as_bytes())
+        .map_err(|_| format!("invalid header name `{}`", name))?;
+    Ok((name.to_string(), value.trim().to_string()))
+}
+
+/// Send a request.
+///
+/// GET requests are retried with exponential backoff on connect errors and
+/// 5xx responses.
+pub async fn send(builder: RequestBuilder) -> reqwest::Result<Response> {
+    let options = http_options();
+    let (client, request) = builder.build_split();
+    let request = request?;
+    let retries = if request.method() == Method::GET {
+        options.retries
+    } else {
+        0
+    };
+
+    let mut attempt = 0;
+    loop {
+        let current = match request.try_clone() {
+            Some(current) if attempt < retries => current,
+            _ => return client.execute(request).await,
+        };
+
+        match client.execute(current).await {
+            Ok(response) if response.status().is_server_error() => {
+                vprintln!("Server error: HTTP {}", response.status());
+            }
+            Err(err) if err.is_connect() => {
+                vprintln!("Connect error: {}", err);
+            }
+            other => return other,
+        }
+
+        attempt += 1;
+        let delay = RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+        vprintln!(
+            "Retrying in {}ms (attempt {}/{})",
+            delay.as_millis(),
+            attempt,
+            retries
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
@@ -0,0 +1,166 @@
+use serde_json::Value;
+
+use crate::error::CliError;
+
+/// Split a dotted path like `provider_settings.enable` or `platform.0.id`,
+/// an empty path addresses the root value
+fn segments(path: &str) -> Vec<&str> {
+    path.split('.').filter(|s| !s.is_empty()).collect()
+}
+
+/// Parse a `path=value` argument
+pub fn parse_assignment(arg: &str) -> Result<(String, String), String> {
+    arg.split_once('=')
+        .map(|(path, value)| (path.trim().to_string(), value.to_string()))
+        .ok_or_else(|| format!("invalid assignment `{}`, expected `path=value`", arg))
+}
+
+/// Look up the value at a dotted path
+pub fn get<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    segments(path)
+        .into_iter()
+        .try_fold(value, |current, key| match current {
+            Value::Object(map) => map.get(key),
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+}
+
+/// Replace the value at a dotted path and return the previous one.
+///
+/// The parent must exist, only the last key may be new.
+pub fn set(value: &mut Value, path: &str, new_value: Value) -> anyhow::Result<Option<Value>> {
+    let segments = segments(path);
+    let Some((last, parents)) = segments.split_last() else {
+        return Ok(Some(std::mem::replace(value, new_value)));
+    };
+
+    let mut current = value;
+    for key in parents {
+        current = match current {
+            Value::Object(map) => map.get_mut(*key),
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+            _ => None,
+        }
+        .ok_or_else(|| CliError::NotFound(format!("Config path `{}` does not exist", path)))?;
+    }
+
+    match current {
+        Value::Object(map) => Ok(map.insert(last.to_string(), new_value)),
+        Value::Array(items) => {
+            let slot = last
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| items.get_mut(i))
+                .ok_or_else(|| {
+                    CliError::NotFound(format!("Array index in `{}` is out of range", path))
+                })?;
+            Ok(Some(std::mem::replace(slot, new_value)))
+        }
+        _ => anyhow::bail!(CliError::Validation(format!(
+            "Config path `{}` does not point into an object or array",
+            path
+        ))),
+    }
+}
+
+/// Parse a command line value into the JSON type of the value it replaces.
+///
+/// Without a current value the input is parsed as JSON, falling back to a
+/// plain string.
+pub fn coerce(raw: &str, current: Option<&Value>) -> anyhow::Result<Value> {
+    let invalid =
+        |expected: &str| CliError::Validation(format!("Expected {}, got `{}`", expected, raw));
+    let value = match current {
+        Some(Value::Bool(_)) => match raw.to_ascii_lowercase().as_str() {
+            "true" | "on" | "yes" | "1" => Value::Bool(true),
+            "false" | "off" | "no" | "0" => Value::Bool(false),
+            _ => anyhow::bail!(invalid("a boolean")),
+        },
+        Some(Value::Number(n)) if n.is_f64() => raw
+            .parse::<f64>()
+            .ok()
+            .and_then(|f| serde_json::Number::from_f64(f).map(Value::Number))
+            .ok_or_else(|| invalid("a number"))?,
+        Some(Value::Number(_)) => raw
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| invalid("an integer"))?,
+        Some(Value::String(_)) => Value::String(raw.to_string()),
+        Some(Value::Array(_)) => match serde_json::from_str::<Value>(raw) {
+            Ok(v @ Value::Array(_)) => v,
+            _ => anyhow::bail!(invalid("a JSON array")),
+        },
+        Some(Value::Object(_)) => match serde_json::from_str::<Value>(raw) {
+            Ok(v @ Value::Object(_)) => v,
+            _ => anyhow::bail!(invalid("a JSON object")),
+        },
+        Some(Value::Null) | None => {
+            serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+        }
+    };
+    Ok(value)
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Collect the paths whose JSON type differs between `old` and `new`.
+///
+/// Keys only present on one side and `null` values are not reported.
+pub fn type_mismatches(old: &Value, new: &Value) -> Vec<String> {
+    fn walk(old: &Value, new: &Value, path: &str, out: &mut Vec<String>) {
+        match (old, new) {
+            (Value::Null, _) | (_, Value::Null) => {}
+            (Value::Object(a), Value::Object(b)) => {
+                for (key, old_value) in a {
+                    if let Some(new_value) = b.get(key) {
+                        let child = if path.is_empty() {
+                            key.clone()
+                        } else {
+                            format!("{}.{}", path, key)
+                        };
+                        walk(old_value, new_value, &child, out);
+                    }
+                }
+            }
+            (a, b) if type_name(a) != type_name(b) => out.push(format!(
+                "{}: expected {}, got {}",
+                if path.is_empty() { "<root>" } else { path },
+                type_name(a),
+                type_name(b)
+            )),
+            _ => {}
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(old, new, "", &mut out);
+    out
+}
+
+/// Deep merge `patch` into `target`, objects are merged key by key and any
+/// other value replaces the target
+pub fn merge(target: &mut Value, patch: &Value) {
+    match (target, patch) {
+        (Value::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                match target.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (target, patch) => *target = patch.clone(),
+    }
+}
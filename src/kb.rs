@@ -0,0 +1,310 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::api::{ApiClient, KnowledgeBase, NewKnowledgeBase};
+use crate::config::ConfigManager;
+use crate::error::CliError;
+use crate::utils::{build_client, confirm, load_credentials};
+use crate::{iprintln, output, vprintln};
+
+/// Local record of uploaded file hashes, so unchanged files are skipped
+const HASH_CACHE_FILE: &str = "astrbot-kb-hashes.json";
+
+/// Uploaded document hashes keyed by `<server>#<kb_id>`, then document name
+type HashCache = BTreeMap<String, BTreeMap<String, String>>;
+
+fn load_hash_cache() -> HashCache {
+    ConfigManager::get_state_file_path(HASH_CACHE_FILE)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_hash_cache(cache: &HashCache) -> anyhow::Result<()> {
+    let path = ConfigManager::get_state_file_path(HASH_CACHE_FILE)?;
+    fs::write(&path, serde_json::to_string_pretty(cache)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Find a knowledge base by name or id
+async fn find_kb(api_client: &ApiClient, kb: &str) -> anyhow::Result<KnowledgeBase> {
+    api_client
+        .get_knowledge_bases()
+        .await?
+        .into_iter()
+        .find(|k| k.kb_name == kb || k.kb_id == kb)
+        .ok_or_else(|| CliError::NotFound(format!("Knowledge base `{}` does not exist", kb)).into())
+}
+
+fn pretty_print(kb: &KnowledgeBase) {
+    println!("Name: {}", kb.kb_name);
+    println!("ID: {}", kb.kb_id);
+    if let Some(description) = kb.description.as_deref().filter(|d| !d.is_empty()) {
+        println!("Description: {}", description);
+    }
+    println!(
+        "Embedding provider: {}",
+        kb.embedding_provider_id.as_deref().unwrap_or("-")
+    );
+    println!(
+        "Rerank provider: {}",
+        kb.rerank_provider_id.as_deref().unwrap_or("-")
+    );
+    println!("Documents: {}", kb.doc_count);
+    println!("Chunks: {}", kb.chunk_count);
+    println!()
+}
+
+/// Expand files and directories into `(path, document name)` pairs.
+///
+/// Directories are walked recursively, hidden entries are skipped and the
+/// document name is the path relative to the given directory.
+fn collect_files(
+    inputs: &[PathBuf],
+    includes: &[glob::Pattern],
+) -> anyhow::Result<Vec<(PathBuf, String)>> {
+    fn walk(dir: &Path, root: &Path, out: &mut Vec<(PathBuf, String)>) -> anyhow::Result<()> {
+        let mut entries = fs::read_dir(dir)
+            .with_context(|| format!("Failed to read {}", dir.display()))?
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|e| e.path());
+        for entry in entries {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            if path.is_dir() {
+                walk(&path, root, out)?;
+            } else {
+                let name = path
+                    .strip_prefix(root)
+                    .unwrap_or(&path)
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                out.push((path, name));
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            walk(input, input, &mut files)?;
+        } else if input.is_file() {
+            let name = input
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            files.push((input.clone(), name));
+        } else {
+            anyhow::bail!(CliError::Validation(format!(
+                "{} does not exist",
+                input.display()
+            )));
+        }
+    }
+
+    if !includes.is_empty() {
+        files.retain(|(path, name)| {
+            let file_name = path.file_name().map(|n| n.to_string_lossy());
+            includes.iter().any(|pattern| {
+                pattern.matches(name) || file_name.as_deref().is_some_and(|f| pattern.matches(f))
+            })
+        });
+    }
+    Ok(files)
+}
+
+pub async fn handle_kb_list() -> anyhow::Result<()> {
+    iprintln!("Fetching knowledge base list...");
+    let api_client = build_client()?;
+    let kbs = api_client.get_knowledge_bases().await?;
+    if output::is_json() {
+        output::print_json(&kbs)
+    } else {
+        kbs.iter().for_each(pretty_print);
+        Ok(())
+    }
+}
+
+pub async fn handle_kb_show(kb: &str) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let kb = find_kb(&api_client, kb).await?;
+    let documents = api_client.get_kb_documents(&kb.kb_id).await?;
+    if output::is_json() {
+        return output::print_json(&serde_json::json!({ "kb": kb, "documents": documents }));
+    }
+    pretty_print(&kb);
+    for document in documents {
+        println!(
+            "  {} ({} bytes, {} chunks)",
+            document.doc_name, document.file_size, document.chunk_count
+        );
+    }
+    Ok(())
+}
+
+pub async fn handle_kb_create(kb: NewKnowledgeBase) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let ret = api_client.create_knowledge_base(&kb).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_kb_delete(kb: &str, yes: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let kb = find_kb(&api_client, kb).await?;
+    if !yes
+        && !confirm(&format!(
+            "Delete knowledge base `{}` and its {} documents?",
+            kb.kb_name, kb.doc_count
+        ))?
+    {
+        output::print_message("Aborted");
+        return Ok(());
+    }
+    let ret = api_client.delete_knowledge_base(&kb.kb_id).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_kb_upload(
+    kb: &str,
+    inputs: &[PathBuf],
+    includes: &[String],
+    force: bool,
+) -> anyhow::Result<()> {
+    let includes = includes
+        .iter()
+        .map(|p| {
+            glob::Pattern::new(p)
+                .map_err(|e| CliError::Validation(format!("Invalid glob `{}`: {}", p, e)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let files = collect_files(inputs, &includes)?;
+    if files.is_empty() {
+        anyhow::bail!(CliError::Validation("No files to upload".to_string()));
+    }
+
+    let credentials = load_credentials()?;
+    let api_client = ApiClient::new(&credentials)?;
+    let kb = find_kb(&api_client, kb).await?;
+    let documents: BTreeMap<String, String> = api_client
+        .get_kb_documents(&kb.kb_id)
+        .await?
+        .into_iter()
+        .map(|d| (d.doc_name, d.doc_id))
+        .collect();
+
+    let mut cache = load_hash_cache();
+    let cache_key = format!("{}#{}", credentials.server_url, kb.kb_id);
+
+    let total_bytes = files
+        .iter()
+        .map(|(path, _)| fs::metadata(path).map_or(0, |m| m.len()))
+        .sum();
+    let progress = if output::is_json() {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(total_bytes)
+    };
+    progress.set_style(
+        ProgressStyle::with_template("{bar:40} {bytes}/{total_bytes} {wide_msg}")
+            .expect("valid progress template"),
+    );
+
+    let (mut uploaded, mut skipped, mut failures) = (0, 0, Vec::new());
+    for (path, name) in &files {
+        progress.set_message(name.clone());
+        let content =
+            fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let size = content.len() as u64;
+        let hash = format!("{:x}", md5::compute(&content));
+
+        let existing = documents.get(name);
+        let cached = cache.get(&cache_key).and_then(|c| c.get(name));
+        if !force && existing.is_some() && cached == Some(&hash) {
+            progress.println(format!("skip   {} (unchanged)", name));
+            skipped += 1;
+            progress.inc(size);
+            continue;
+        }
+
+        let result = async {
+            api_client
+                .upload_kb_document(&kb.kb_id, name, content)
+                .await?;
+            // Drop the old version of a changed document only once the new
+            // one is in, a failed upload must not lose it
+            if let Some(doc_id) = existing {
+                vprintln!("Removing the previous version of {} ({})", name, doc_id);
+                api_client
+                    .delete_kb_document(&kb.kb_id, doc_id)
+                    .await
+                    .context("uploaded, but removing the previous version failed")?;
+            }
+            anyhow::Ok(())
+        }
+        .await;
+
+        match result {
+            Ok(_) => {
+                progress.println(format!("upload {}", name));
+                cache
+                    .entry(cache_key.clone())
+                    .or_default()
+                    .insert(name.clone(), hash);
+                save_hash_cache(&cache)?;
+                uploaded += 1;
+            }
+            Err(err) => {
+                progress.println(format!("fail   {}: {:#}", name, err));
+                failures.push(name.clone());
+            }
+        }
+        progress.inc(size);
+    }
+    progress.finish_and_clear();
+
+    let summary = format!(
+        "{} uploaded, {} unchanged, {} failed",
+        uploaded,
+        skipped,
+        failures.len()
+    );
+    if !failures.is_empty() {
+        anyhow::bail!(CliError::Api(format!(
+            "{}: {}",
+            summary,
+            failures.join(", ")
+        )));
+    }
+    output::print_message(&summary);
+    Ok(())
+}
+
+pub async fn handle_kb_query(kb: &str, query: &str, top_k: u32) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let kb = find_kb(&api_client, kb).await?;
+    let results = api_client.retrieve_kb(&kb.kb_name, query, top_k).await?;
+    if output::is_json() {
+        return output::print_json(&results);
+    }
+    for (i, result) in results.iter().enumerate() {
+        println!("#{} score {:.4} — {}", i + 1, result.score, result.doc_name);
+        println!("{}", result.content.trim());
+        println!();
+    }
+    if results.is_empty() {
+        iprintln!("No results");
+    }
+    Ok(())
+}
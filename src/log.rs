@@ -1,4 +1,4 @@
-use crate::{iprintln, utils::build_client};
+use crate::{iprintln, output, utils::build_client};
 use tokio::io::AsyncWriteExt;
 
 pub async fn handle_live_log(flush: bool) -> anyhow::Result<()> {
@@ -18,6 +18,6 @@ pub async fn handle_history_log(output_file: String) -> anyhow::Result<()> {
         file.write_all(line.as_bytes()).await?;
         file.write_all(b"\n").await?;
     }
-    iprintln!("Log history saved to {}", output_file);
+    output::print_message(&format!("Log history saved to {}", output_file));
     Ok(())
 }
@@ -1,8 +1,9 @@
 use crate::config::{Config, ConfigManager};
-use crate::{iprintln, vprintln};
-use reqwest;
+use crate::error::CliError;
+use crate::{http, iprintln, output, vprintln};
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
-use std::process;
+use std::path::PathBuf;
 
 #[derive(Serialize)]
 struct LoginRequest {
@@ -18,82 +19,121 @@ struct LoginResponse {
 }
 
 #[derive(Deserialize)]
-#[allow(dead_code)]
-struct LoginData {
-    token: String,
-    username: String,
-    change_pwd_hint: bool,
+pub struct LoginData {
+    pub token: String,
+    pub username: String,
+    #[serde(default)]
+    pub change_pwd_hint: bool,
 }
 
-pub async fn handle_login(username: String, password: String, url: String) {
-    iprintln!("Starting login process...");
-    vprintln!("Server: {}", url);
-    vprintln!("Username: {}", username);
-
-    // MD5 encrypt the password
-    let password_hash = format!("{:x}", md5::compute(password.as_bytes()));
+/// MD5 hash a plain text password the same way the dashboard does
+pub fn hash_password(password: &str) -> String {
+    format!("{:x}", md5::compute(password.as_bytes()))
+}
 
-    // Prepare the login request
+/// Send a login request with an already hashed password
+pub async fn request_login(
+    profile: &Config,
+    username: String,
+    password_hash: String,
+) -> anyhow::Result<LoginData> {
     let login_request = LoginRequest {
         username,
         password: password_hash,
     };
 
     // Construct the full API URL
-    let api_url = format!("{}/api/auth/login", url.trim_end_matches('/'));
+    let api_url = format!(
+        "{}/api/auth/login",
+        profile.server_url.trim_end_matches('/')
+    );
     vprintln!("API URL: {}", api_url);
 
     // Create HTTP client
-    let client = reqwest::Client::new();
+    let client = http::build_http_client(profile)?;
 
     // Send POST request
     vprintln!("Sending login request...");
-    match client.post(&api_url).json(&login_request).send().await {
-        Ok(response) => {
-            let status_code = response.status();
-            vprintln!("Response received: HTTP {}", status_code);
-
-            match response.json::<LoginResponse>().await {
-                Ok(login_response) => {
-                    if login_response.status == "ok" {
-                        if let Some(data) = login_response.data {
-                            iprintln!("Login successful!");
-
-                            // Create credentials object
-                            let credentials = Config {
-                                token: data.token.clone(),
-                                server_url: url.clone(),
-                                username: data.username.clone(),
-                            };
-
-                            // Save to persistent config file
-                            if let Err(e) = ConfigManager::save_credentials(&credentials) {
-                                eprintln!("Warning: Failed to save to config file: {}", e);
-                            }
-
-                            iprintln!("Login complete! You can now use other commands.");
-                        } else {
-                            eprintln!("❌ Error: Login response missing data");
-                            process::exit(1);
-                        }
-                    } else {
-                        let error_msg = login_response
-                            .message
-                            .unwrap_or("Unknown error".to_string());
-                        eprintln!("❌ Login failed: {}", error_msg);
-
-                        process::exit(1);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("❌ Error parsing login response: {}", e);
-                    process::exit(1);
-                }
-            }
-        }
-        Err(e) => {
-            eprintln!("❌ Error sending login request: {}", e);
-            process::exit(1);
-        }
+    let builder = http::apply_headers(client.post(&api_url), &profile.headers);
+    let response = http::send(builder.json(&login_request))
+        .await
+        .map_err(CliError::network)
+        .context("Error sending login request")?;
+    vprintln!("Response received: HTTP {}", response.status());
+
+    let login_response = response
+        .json::<LoginResponse>()
+        .await
+        .map_err(|e| CliError::Api(format!("Error parsing login response: {}", e)))?;
+
+    if login_response.status != "ok" {
+        let error_msg = login_response
+            .message
+            .unwrap_or("Unknown error".to_string());
+        anyhow::bail!(CliError::Auth(format!("Login failed: {}", error_msg)));
+    }
+
+    login_response
+        .data
+        .ok_or_else(|| CliError::Api("Login response missing data".to_string()).into())
+}
+
+/// Warn the user when the dashboard still uses its default credentials
+pub fn warn_change_pwd_hint(data: &LoginData) {
+    if data.change_pwd_hint {
+        eprintln!();
+        eprintln!("⚠️  WARNING: This dashboard is still using the default username/password!");
+        eprintln!("⚠️  Anyone who can reach it can take control of your bot.");
+        eprintln!("⚠️  Change them now with: astrbot account update");
+        eprintln!();
+    }
+}
+
+/// Resolve a certificate path given at login to an absolute one, so the
+/// saved profile works from any directory
+fn resolve_path(path: Option<PathBuf>, flag: &str) -> anyhow::Result<Option<PathBuf>> {
+    path.map(|path| {
+        path.canonicalize().map_err(|e| {
+            CliError::Validation(format!("--{} {}: {}", flag, path.display(), e)).into()
+        })
+    })
+    .transpose()
+}
+
+/// Log in and save the credentials, `profile` carries the server URL and
+/// TLS settings, its token and username are filled in from the response
+pub async fn handle_login(
+    username: String,
+    password: String,
+    mut profile: Config,
+) -> anyhow::Result<()> {
+    profile.ca_cert = resolve_path(profile.ca_cert, "ca-cert")?;
+    profile.client_cert = resolve_path(profile.client_cert, "client-cert")?;
+    profile.client_key = resolve_path(profile.client_key, "client-key")?;
+
+    iprintln!("Starting login process...");
+    vprintln!("Server: {}", profile.server_url);
+    vprintln!("Username: {}", username);
+
+    // MD5 encrypt the password
+    let password_hash = hash_password(&password);
+
+    let data = request_login(&profile, username, password_hash).await?;
+    iprintln!("Login successful!");
+
+    // Create credentials object
+    let credentials = Config {
+        token: data.token.clone(),
+        username: data.username.clone(),
+        ..profile
+    };
+
+    // Save to persistent config file
+    if let Err(e) = ConfigManager::save_credentials(&credentials) {
+        eprintln!("Warning: Failed to save to config file: {}", e);
     }
+
+    warn_change_pwd_hint(&data);
+    output::print_message("Login complete! You can now use other commands.");
+    Ok(())
 }
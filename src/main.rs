@@ -1,17 +1,53 @@
+use std::time::Duration;
+
+use account::handle_account_update;
+use anyhow::Context;
 use clap::Parser;
-use cli::{handle_plugin_command, Cli, Commands, LogCommands};
+use cli::{
+    handle_plugin_command, AccountCommands, BackupCommands, Cli, CommandCommands, Commands,
+    ConfigCommands, ConversationCommands, CronCommands, IdListCommands, KbCommands, LogCommands,
+    McpCommands, PersonaCommands, PlatformCommands, ProviderCommands, SessionCommands, T2iCommands,
+    T2iTemplateCommands, TestCommands, ToolCommands, UpdateCommands, WhitelistCommands,
+};
+use core_config::{handle_config_edit, handle_config_get, handle_config_set};
 use log::{handle_history_log, handle_live_log};
 
 use login::handle_login;
 use stat::handle_stat;
 
+mod account;
 mod api;
+mod backup;
+mod chat;
 mod cli;
+mod command;
 mod config;
+mod conversation;
+mod core_config;
+mod cron;
+mod error;
+mod http;
+mod jsonpath;
+mod kb;
 mod log;
 mod login;
+mod mcp;
+mod message;
+mod output;
+mod persona;
+mod platform;
 mod plugin;
+mod plugin_config;
+mod promote;
+mod provider;
+mod schema;
+mod server;
+mod session;
 mod stat;
+mod t2i;
+mod test_runner;
+mod tool;
+mod update;
 mod utils;
 mod verbose;
 
@@ -21,36 +57,415 @@ async fn main() {
 
     // Initialize verbose mode
     verbose::init_verbose(cli.verbose);
+    output::init_json(cli.json);
+    http::init_http_options(http::HttpOptions {
+        timeout: Duration::from_secs(cli.timeout),
+        retries: cli.retries,
+        insecure: cli.insecure,
+    });
+    config::init_profile(cli.profile);
 
-    match cli.command {
-        Commands::Plugin { action } => {
-            handle_plugin_command(action).await;
-        }
+    if let Err(err) = run(cli.command).await {
+        std::process::exit(error::render(&err));
+    }
+}
+
+async fn run(command: Commands) -> anyhow::Result<()> {
+    match command {
+        Commands::Plugin { action } => handle_plugin_command(action).await,
         Commands::Login {
             username,
             password,
             server,
-        } => handle_login(username, password, server).await,
-        Commands::Stat => {
-            let ret = handle_stat().await;
-            match ret {
-                Ok(ret) => ret.pretty_print(),
-                Err(e) => eprintln!("Error retrieving statistics: {}", e),
+            ca_cert,
+            client_cert,
+            client_key,
+            proxy,
+            headers,
+        } => {
+            let profile = config::Config {
+                token: String::new(),
+                server_url: server,
+                username: username.clone(),
+                ca_cert,
+                client_cert,
+                client_key,
+                proxy,
+                headers: headers.into_iter().collect(),
+            };
+            handle_login(username, password, profile).await
+        }
+        Commands::Account { action } => match action {
+            AccountCommands::Update {
+                new_username,
+                new_password,
+            } => handle_account_update(new_username, new_password)
+                .await
+                .context("Error updating account"),
+        },
+        Commands::Config { conf_id, action } => match action {
+            ConfigCommands::Get { path } => handle_config_get(&conf_id, path).await,
+            ConfigCommands::Set { path, value } => handle_config_set(&conf_id, &path, &value).await,
+            ConfigCommands::Edit { yes } => handle_config_edit(&conf_id, yes).await,
+        },
+        Commands::Provider { action } => match action {
+            ProviderCommands::List { provider_type } => {
+                provider::handle_provider_list(provider_type).await
+            }
+            ProviderCommands::Show { id, reveal } => {
+                provider::handle_provider_show(&id, reveal).await
+            }
+            ProviderCommands::Add {
+                id,
+                provider_source,
+                provider_type,
+                disabled,
+                from_file,
+                fields,
+            } => {
+                provider::handle_provider_add(
+                    id,
+                    provider_source,
+                    provider_type,
+                    disabled,
+                    from_file.as_deref(),
+                    fields,
+                )
+                .await
+            }
+            ProviderCommands::Update { id, fields } => {
+                provider::handle_provider_update(&id, fields).await
+            }
+            ProviderCommands::Remove { id, yes } => {
+                provider::handle_provider_remove(&id, yes).await
+            }
+            ProviderCommands::Enable { id } => provider::handle_provider_toggle(&id, true).await,
+            ProviderCommands::Disable { id } => provider::handle_provider_toggle(&id, false).await,
+            ProviderCommands::Test { id } => provider::handle_provider_test(&id).await,
+            ProviderCommands::Models { id } => provider::handle_provider_models(&id).await,
+        },
+        Commands::Platform { action } => match action {
+            PlatformCommands::List => platform::handle_platform_list().await,
+            PlatformCommands::Show { id, reveal } => {
+                platform::handle_platform_show(&id, reveal).await
+            }
+            PlatformCommands::Add {
+                adapter,
+                id,
+                set,
+                from_file,
+                no_input,
+            } => {
+                platform::handle_platform_add(adapter, id, set, from_file.as_deref(), no_input)
+                    .await
+            }
+            PlatformCommands::Update { id, set, no_input } => {
+                platform::handle_platform_update(&id, set, no_input).await
+            }
+            PlatformCommands::Remove { id, yes } => {
+                platform::handle_platform_remove(&id, yes).await
+            }
+            PlatformCommands::Enable { id } => platform::handle_platform_toggle(&id, true).await,
+            PlatformCommands::Disable { id } => platform::handle_platform_toggle(&id, false).await,
+        },
+        Commands::Persona { action } => match action {
+            PersonaCommands::List => persona::handle_persona_list().await,
+            PersonaCommands::Show { persona_id } => persona::handle_persona_show(&persona_id).await,
+            PersonaCommands::Create { persona_id, fields } => {
+                persona::handle_persona_create(persona_id, fields).await
+            }
+            PersonaCommands::Update { persona_id, fields } => {
+                persona::handle_persona_update(&persona_id, fields).await
+            }
+            PersonaCommands::Delete { persona_id, yes } => {
+                persona::handle_persona_delete(&persona_id, yes).await
+            }
+            PersonaCommands::Export { dir } => persona::handle_persona_export(&dir).await,
+            PersonaCommands::Import { dir, dry_run } => {
+                persona::handle_persona_import(&dir, dry_run).await
+            }
+        },
+        Commands::Conversation { action } => match action {
+            ConversationCommands::List {
+                filter,
+                page,
+                page_size,
+            } => conversation::handle_conversation_list(filter, page, page_size).await,
+            ConversationCommands::Show { cid, user } => {
+                conversation::handle_conversation_show(&cid, user).await
+            }
+            ConversationCommands::Export {
+                filter,
+                format,
+                output_file,
+            } => {
+                conversation::handle_conversation_export(filter, format, output_file.as_deref())
+                    .await
+            }
+            ConversationCommands::Delete { cid, user, yes } => {
+                conversation::handle_conversation_delete(&cid, user, yes).await
             }
+        },
+        Commands::Kb { action } => match action {
+            KbCommands::List => kb::handle_kb_list().await,
+            KbCommands::Show { kb } => kb::handle_kb_show(&kb).await,
+            KbCommands::Create {
+                name,
+                description,
+                embedding_provider,
+                rerank_provider,
+                chunk_size,
+                chunk_overlap,
+            } => {
+                kb::handle_kb_create(api::NewKnowledgeBase {
+                    kb_name: name,
+                    description,
+                    embedding_provider_id: embedding_provider,
+                    rerank_provider_id: rerank_provider,
+                    chunk_size,
+                    chunk_overlap,
+                })
+                .await
+            }
+            KbCommands::Delete { kb, yes } => kb::handle_kb_delete(&kb, yes).await,
+            KbCommands::Upload {
+                kb,
+                paths,
+                includes,
+                force,
+            } => kb::handle_kb_upload(&kb, &paths, &includes, force).await,
+            KbCommands::Query { kb, text, top_k } => kb::handle_kb_query(&kb, &text, top_k).await,
+        },
+        Commands::Mcp { action } => match action {
+            McpCommands::List { tools } => mcp::handle_mcp_list(tools).await,
+            McpCommands::Add {
+                name,
+                fields,
+                disabled,
+                from_file,
+            } => mcp::handle_mcp_add(name, fields, disabled, from_file.as_deref()).await,
+            McpCommands::Update { name, fields } => mcp::handle_mcp_update(&name, fields).await,
+            McpCommands::Remove { name, yes } => mcp::handle_mcp_remove(&name, yes).await,
+            McpCommands::Enable { name } => mcp::handle_mcp_toggle(&name, true).await,
+            McpCommands::Disable { name } => mcp::handle_mcp_toggle(&name, false).await,
+            McpCommands::Test { name } => mcp::handle_mcp_test(&name).await,
+        },
+        Commands::Tool { action } => match action {
+            ToolCommands::List => tool::handle_tool_list().await,
+            ToolCommands::Show { name } => tool::handle_tool_show(&name).await,
+            ToolCommands::On { name } => tool::handle_tool_toggle(&name, true).await,
+            ToolCommands::Off { name } => tool::handle_tool_toggle(&name, false).await,
+        },
+        Commands::Command { action } => match action {
+            CommandCommands::List { plugin } => command::handle_command_list(plugin).await,
+            CommandCommands::Conflicts => command::handle_command_conflicts().await,
+            CommandCommands::On { command, plugin } => {
+                command::handle_command_toggle(&command, plugin.as_deref(), true).await
+            }
+            CommandCommands::Off { command, plugin } => {
+                command::handle_command_toggle(&command, plugin.as_deref(), false).await
+            }
+            CommandCommands::Rename {
+                command,
+                new_name,
+                aliases,
+                no_aliases,
+                plugin,
+                force,
+            } => {
+                let aliases = (no_aliases || !aliases.is_empty()).then_some(aliases);
+                command::handle_command_rename(
+                    &command,
+                    &new_name,
+                    aliases,
+                    plugin.as_deref(),
+                    force,
+                )
+                .await
+            }
+        },
+        Commands::Chat {
+            message,
+            session,
+            images,
+            provider,
+            model,
+        } => chat::handle_chat(message, session, &images, provider, model).await,
+        Commands::Test { action } => match action {
+            TestCommands::Run {
+                file,
+                jobs,
+                filter,
+                format,
+                output_file,
+            } => {
+                test_runner::handle_test_run(
+                    &file,
+                    jobs,
+                    filter.as_deref(),
+                    format,
+                    output_file.as_deref(),
+                )
+                .await
+            }
+        },
+        Commands::Update { action } => match action {
+            UpdateCommands::Check => update::handle_update_check().await,
+            UpdateCommands::Apply {
+                version,
+                yes,
+                no_wait,
+                wait_timeout,
+            } => {
+                update::handle_update_apply(
+                    version,
+                    yes,
+                    no_wait,
+                    Duration::from_secs(wait_timeout),
+                )
+                .await
+            }
+            UpdateCommands::Dashboard { force } => update::handle_update_dashboard(force).await,
+        },
+        Commands::Restart { yes, wait_timeout } => {
+            server::handle_restart(yes, Duration::from_secs(wait_timeout)).await
         }
-        Commands::Log { action } => match action {
-            LogCommands::Live { flush } => {
-                if let Err(err) = handle_live_log(flush).await {
-                    eprintln!("Error fetching live log: {}", err);
-                    std::process::exit(1);
+        Commands::Backup { action } => match action {
+            BackupCommands::Create {
+                output_file,
+                only,
+                include_secrets,
+            } => backup::handle_backup_create(output_file, &only, include_secrets).await,
+            BackupCommands::Restore {
+                file,
+                only,
+                dry_run,
+                yes,
+            } => backup::handle_backup_restore(&file, &only, dry_run, yes).await,
+        },
+        Commands::Session { action } => match action {
+            SessionCommands::List { platform } => {
+                session::handle_session_list(platform.as_deref()).await
+            }
+            SessionCommands::Set {
+                umo,
+                provider,
+                persona,
+                llm,
+            } => {
+                session::handle_session_set(&umo, provider.as_deref(), persona.as_deref(), llm)
+                    .await
+            }
+            SessionCommands::Admin { action } => match action {
+                IdListCommands::List => session::handle_admin_list().await,
+                IdListCommands::Add { ids } => session::handle_admin_change(&ids, true).await,
+                IdListCommands::Remove { ids } => session::handle_admin_change(&ids, false).await,
+            },
+            SessionCommands::Whitelist { action } => match action {
+                WhitelistCommands::List => session::handle_whitelist_list().await,
+                WhitelistCommands::Add { ids } => {
+                    session::handle_whitelist_change(&ids, true).await
+                }
+                WhitelistCommands::Remove { ids } => {
+                    session::handle_whitelist_change(&ids, false).await
                 }
+                WhitelistCommands::On => session::handle_whitelist_toggle(true).await,
+                WhitelistCommands::Off => session::handle_whitelist_toggle(false).await,
+            },
+            SessionCommands::Blacklist { action } => match action {
+                IdListCommands::List => session::handle_blacklist_list().await,
+                IdListCommands::Add { ids } => session::handle_blacklist_change(&ids, true).await,
+                IdListCommands::Remove { ids } => {
+                    session::handle_blacklist_change(&ids, false).await
+                }
+            },
+        },
+        Commands::Cron { action } => match action {
+            CronCommands::List => cron::handle_cron_list().await,
+            CronCommands::Show { job } => cron::handle_cron_show(&job).await,
+            CronCommands::Add {
+                name,
+                expression,
+                session,
+                message,
+                timezone,
+                description,
+                paused,
+                dry_run,
+            } => {
+                let job = api::NewCronJob {
+                    name,
+                    description,
+                    cron_expression: expression,
+                    timezone,
+                    payload: api::CronPayload {
+                        session,
+                        note: message,
+                    },
+                    enabled: !paused,
+                };
+                cron::handle_cron_add(job, dry_run).await
             }
-            LogCommands::History { output_file } => {
-                if let Err(err) = handle_history_log(output_file).await {
-                    eprintln!("Error fetching log history: {}", err);
-                    std::process::exit(1);
+            CronCommands::Remove { job, yes } => cron::handle_cron_remove(&job, yes).await,
+            CronCommands::Pause { job } => cron::handle_cron_toggle(&job, false).await,
+            CronCommands::Resume { job } => cron::handle_cron_toggle(&job, true).await,
+            CronCommands::RunNow { job } => cron::handle_cron_run_now(&job).await,
+        },
+        Commands::T2i { action } => match action {
+            T2iCommands::Template { action } => match action {
+                T2iTemplateCommands::List => t2i::handle_template_list().await,
+                T2iTemplateCommands::Pull { dir } => t2i::handle_template_pull(&dir).await,
+                T2iTemplateCommands::Push { dir, dry_run } => {
+                    t2i::handle_template_push(&dir, dry_run).await
                 }
+                T2iTemplateCommands::SetActive { name } => {
+                    t2i::handle_template_set_active(&name).await
+                }
+                T2iTemplateCommands::Reset { yes } => t2i::handle_template_reset(yes).await,
+            },
+            T2iCommands::Render {
+                template,
+                text,
+                output_file,
+                endpoint,
+            } => t2i::handle_render(&template, &text, &output_file, endpoint).await,
+        },
+        Commands::Send { session, content } => message::handle_send(&session, content).await,
+        Commands::Broadcast {
+            platform,
+            groups_file,
+            content,
+            interval,
+            dry_run,
+            yes,
+        } => {
+            message::handle_broadcast(&platform, &groups_file, content, interval, dry_run, yes)
+                .await
+        }
+        Commands::Diff { from, to, only } => promote::handle_diff(&from, &to, &only).await,
+        Commands::Promote {
+            from,
+            to,
+            only,
+            dry_run,
+            yes,
+        } => promote::handle_promote(&from, &to, &only, dry_run, yes).await,
+        Commands::Stat => {
+            let stat = handle_stat().await.context("Error retrieving statistics")?;
+            if output::is_json() {
+                output::print_json(&stat)?;
+            } else {
+                stat.pretty_print();
             }
+            Ok(())
+        }
+        Commands::Log { action } => match action {
+            LogCommands::Live { flush } => handle_live_log(flush)
+                .await
+                .context("Error fetching live log"),
+            LogCommands::History { output_file } => handle_history_log(output_file)
+                .await
+                .context("Error fetching log history"),
         },
     }
 }
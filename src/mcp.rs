@@ -0,0 +1,385 @@
+use std::path::Path;
+
+use anyhow::Context;
+use serde_json::{Map, Value};
+
+use crate::api::ApiClient;
+use crate::cli::McpServerFields;
+use crate::error::CliError;
+use crate::utils::{build_client, confirm, mask_secrets};
+use crate::{iprintln, output};
+
+/// Keys the server list adds on top of the stored server config
+const STATUS_KEYS: [&str; 4] = ["name", "active", "tools", "errlogs"];
+
+pub fn server_name(server: &Value) -> &str {
+    server
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+pub fn is_active(server: &Value) -> bool {
+    server
+        .get("active")
+        .and_then(Value::as_bool)
+        .unwrap_or(true)
+}
+
+/// The stored config of a listed server, without its status fields
+pub fn server_config(server: &Value) -> Map<String, Value> {
+    let mut config = server.as_object().cloned().unwrap_or_default();
+    config.retain(|key, _| !STATUS_KEYS.contains(&key.as_str()));
+    config
+}
+
+fn tool_names(server: &Value) -> Vec<&str> {
+    server
+        .get("tools")
+        .and_then(Value::as_array)
+        .map(|tools| {
+            tools
+                .iter()
+                .filter_map(|tool| tool.as_str().or_else(|| tool.get("name")?.as_str()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn find_server(api_client: &ApiClient, name: &str) -> anyhow::Result<Value> {
+    api_client
+        .get_mcp_servers()
+        .await?
+        .into_iter()
+        .find(|s| server_name(s) == name)
+        .ok_or_else(|| CliError::NotFound(format!("MCP server `{}` does not exist", name)).into())
+}
+
+/// Merge object entries into `config[key]`, creating the object if needed
+fn merge_entries(config: &mut Map<String, Value>, key: &str, entries: Vec<(String, String)>) {
+    if entries.is_empty() {
+        return;
+    }
+    let target = config
+        .entry(key)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(map) = target {
+        for (name, value) in entries {
+            map.insert(name, Value::String(value));
+        }
+    }
+}
+
+/// Apply the command line fields to a server config.
+///
+/// Giving `--command` turns the server into a stdio one and `--url` into a
+/// remote one, dropping the keys of the other kind.
+fn apply_fields(config: &mut Map<String, Value>, fields: McpServerFields) -> anyhow::Result<()> {
+    let stdio_only = !fields.args.is_empty() || !fields.env.is_empty();
+    let remote_only = fields.transport.is_some() || !fields.headers.is_empty();
+
+    if let Some(command) = fields.command {
+        for key in ["url", "transport", "headers"] {
+            config.remove(key);
+        }
+        config.insert("command".to_string(), Value::String(command));
+    } else if let Some(url) = fields.url {
+        for key in ["command", "args", "env"] {
+            config.remove(key);
+        }
+        config.insert("url".to_string(), Value::String(url));
+        config
+            .entry("transport")
+            .or_insert_with(|| Value::String("sse".to_string()));
+    }
+
+    if config.contains_key("command") {
+        if remote_only {
+            anyhow::bail!(CliError::Validation(
+                "--transport and --header only apply to URL servers".to_string()
+            ));
+        }
+    } else if config.contains_key("url") {
+        if stdio_only {
+            anyhow::bail!(CliError::Validation(
+                "--arg and --env only apply to command servers".to_string()
+            ));
+        }
+    } else {
+        anyhow::bail!(CliError::Validation(
+            "A server needs either --command or --url".to_string()
+        ));
+    }
+
+    if !fields.args.is_empty() {
+        config.insert(
+            "args".to_string(),
+            fields.args.into_iter().map(Value::String).collect(),
+        );
+    }
+    merge_entries(config, "env", fields.env);
+    if let Some(transport) = fields.transport {
+        config.insert(
+            "transport".to_string(),
+            Value::String(transport.as_str().to_string()),
+        );
+    }
+    merge_entries(config, "headers", fields.headers);
+    Ok(())
+}
+
+/// Convert a Claude Desktop style server definition to AstrBot's format.
+///
+/// Returns the config and whether the server should be active.
+fn normalize_imported(
+    name: &str,
+    definition: &Value,
+) -> anyhow::Result<(Map<String, Value>, bool)> {
+    let mut config = definition
+        .as_object()
+        .cloned()
+        .ok_or_else(|| CliError::Validation(format!("Server `{}` must be a JSON object", name)))?;
+    let active = !config
+        .remove("disabled")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if let Some(kind) = config.remove("type") {
+        match kind.as_str() {
+            Some("sse") => {
+                config.insert("transport".to_string(), Value::String("sse".to_string()));
+            }
+            Some("http" | "streamable-http" | "streamable_http") => {
+                config.insert(
+                    "transport".to_string(),
+                    Value::String("streamable_http".to_string()),
+                );
+            }
+            Some("stdio") => {}
+            _ => anyhow::bail!(CliError::Validation(format!(
+                "Server `{}` has an unknown type {}",
+                name, kind
+            ))),
+        }
+    }
+    if config.contains_key("url") {
+        config
+            .entry("transport")
+            .or_insert_with(|| Value::String("sse".to_string()));
+    } else if !config.contains_key("command") {
+        anyhow::bail!(CliError::Validation(format!(
+            "Server `{}` needs either `command` or `url`",
+            name
+        )));
+    }
+    Ok((config, active))
+}
+
+fn pretty_print(server: &Value, with_tools: bool) {
+    let config = server_config(server);
+    println!("Name: {}", server_name(server));
+    println!("Enabled: {}", is_active(server));
+    if let Some(command) = config.get("command").and_then(Value::as_str) {
+        let args: Vec<&str> = config
+            .get("args")
+            .and_then(Value::as_array)
+            .map(|args| args.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        println!("Transport: stdio");
+        println!("Command: {} {}", command, args.join(" "));
+    } else {
+        println!(
+            "Transport: {}",
+            config
+                .get("transport")
+                .and_then(Value::as_str)
+                .unwrap_or("sse")
+        );
+        println!(
+            "URL: {}",
+            config.get("url").and_then(Value::as_str).unwrap_or("-")
+        );
+    }
+    let tools = tool_names(server);
+    println!("Tools: {}", tools.len());
+    if with_tools {
+        for tool in tools {
+            println!("  - {}", tool);
+        }
+    }
+    println!()
+}
+
+pub async fn handle_mcp_list(with_tools: bool) -> anyhow::Result<()> {
+    iprintln!("Fetching MCP server list...");
+    let api_client = build_client()?;
+    let mut servers = api_client.get_mcp_servers().await?;
+
+    if output::is_json() {
+        servers.iter_mut().for_each(mask_secrets);
+        output::print_json(&servers)?;
+    } else {
+        servers.iter().for_each(|s| pretty_print(s, with_tools));
+    }
+    Ok(())
+}
+
+pub async fn handle_mcp_add(
+    name: Option<String>,
+    fields: McpServerFields,
+    disabled: bool,
+    from_file: Option<&Path>,
+) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    if let Some(file) = from_file {
+        return import_servers(&api_client, file, name.as_deref(), disabled).await;
+    }
+
+    let name = name.ok_or_else(|| CliError::Validation("Specify the server name".to_string()))?;
+    let mut config = Map::new();
+    apply_fields(&mut config, fields)?;
+    let ret = api_client.add_mcp_server(&name, !disabled, &config).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+/// Add every server of an `mcpServers` file, updating the ones that exist
+async fn import_servers(
+    api_client: &ApiClient,
+    file: &Path,
+    only: Option<&str>,
+    disabled: bool,
+) -> anyhow::Result<()> {
+    let content = tokio::fs::read_to_string(file)
+        .await
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let document: Value = serde_json::from_str(&content)
+        .map_err(|e| CliError::Validation(format!("Invalid JSON in {}: {}", file.display(), e)))?;
+    let definitions = document
+        .get("mcpServers")
+        .unwrap_or(&document)
+        .as_object()
+        .ok_or_else(|| {
+            CliError::Validation(format!("{} has no `mcpServers` object", file.display()))
+        })?;
+    if let Some(only) = only {
+        if !definitions.contains_key(only) {
+            anyhow::bail!(CliError::NotFound(format!(
+                "Server `{}` is not defined in {}",
+                only,
+                file.display()
+            )));
+        }
+    }
+
+    let existing: Vec<String> = api_client
+        .get_mcp_servers()
+        .await?
+        .iter()
+        .map(|s| server_name(s).to_string())
+        .collect();
+
+    // Check every entry before touching the server
+    let servers = definitions
+        .iter()
+        .filter(|(name, _)| only.is_none_or(|only| only == name.as_str()))
+        .map(|(name, definition)| {
+            normalize_imported(name, definition).map(|(config, active)| (name, config, active))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let mut results = Vec::new();
+    for (name, config, active) in servers {
+        let active = active && !disabled;
+        let (action, ret) = if existing.contains(name) {
+            (
+                "updated",
+                api_client.update_mcp_server(name, active, &config).await,
+            )
+        } else {
+            (
+                "added",
+                api_client.add_mcp_server(name, active, &config).await,
+            )
+        };
+        match ret {
+            Ok(_) => {
+                iprintln!("{}: {}", name, action);
+                results.push(serde_json::json!({ "name": name, "status": action }));
+            }
+            Err(e) => {
+                iprintln!("{}: failed, {:#}", name, e);
+                results.push(
+                    serde_json::json!({ "name": name, "status": "failed", "error": format!("{:#}", e) }),
+                );
+            }
+        }
+    }
+
+    if output::is_json() {
+        output::print_json(&results)?;
+    }
+    let failed = results.iter().filter(|r| r["status"] == "failed").count();
+    if failed > 0 {
+        anyhow::bail!(CliError::Api(format!(
+            "{} of {} servers failed to import",
+            failed,
+            results.len()
+        )));
+    }
+    Ok(())
+}
+
+pub async fn handle_mcp_update(name: &str, fields: McpServerFields) -> anyhow::Result<()> {
+    if fields.command.is_none()
+        && fields.url.is_none()
+        && fields.args.is_empty()
+        && fields.env.is_empty()
+        && fields.transport.is_none()
+        && fields.headers.is_empty()
+    {
+        anyhow::bail!(CliError::Validation("Nothing to update".to_string()));
+    }
+    let api_client = build_client()?;
+    let server = find_server(&api_client, name).await?;
+    let mut config = server_config(&server);
+    apply_fields(&mut config, fields)?;
+    let ret = api_client
+        .update_mcp_server(name, is_active(&server), &config)
+        .await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_mcp_remove(name: &str, yes: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    find_server(&api_client, name).await?;
+    if !yes && !confirm(&format!("Remove MCP server `{}`?", name))? {
+        output::print_message("Aborted");
+        return Ok(());
+    }
+    let ret = api_client.delete_mcp_server(name).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_mcp_toggle(name: &str, enable: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let server = find_server(&api_client, name).await?;
+    let ret = api_client
+        .update_mcp_server(name, enable, &server_config(&server))
+        .await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_mcp_test(name: &str) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let server = find_server(&api_client, name).await?;
+    iprintln!("Connecting to MCP server `{}`...", name);
+    let ret = api_client.test_mcp_server(&server_config(&server)).await?;
+    output::print_message(&ret);
+    Ok(())
+}
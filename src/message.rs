@@ -0,0 +1,202 @@
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Context;
+use serde_json::json;
+
+use crate::api::{ApiClient, Attachment};
+use crate::cli::MessageContent;
+use crate::error::CliError;
+use crate::utils::{build_client, confirm};
+use crate::{iprintln, output, vprintln};
+
+/// Read the text and image of a message, at least one is required
+async fn load_content(content: MessageContent) -> anyhow::Result<(String, Option<Attachment>)> {
+    let text = match content.text.as_deref() {
+        Some("-") => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            text
+        }
+        Some(text) => text.to_string(),
+        None => String::new(),
+    };
+    let image = match content.image {
+        Some(path) => {
+            let bytes = tokio::fs::read(&path)
+                .await
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let file_name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "image".to_string());
+            Some(Attachment {
+                file_name,
+                content: bytes,
+            })
+        }
+        None => None,
+    };
+    if text.trim().is_empty() && image.is_none() {
+        anyhow::bail!(CliError::Validation(
+            "Nothing to send, pass --text or --image".to_string()
+        ));
+    }
+    Ok((text, image))
+}
+
+/// Check that `umo` looks like `platform:message_type:session_id`
+fn check_umo(umo: &str) -> anyhow::Result<()> {
+    let parts: Vec<&str> = umo.splitn(3, ':').collect();
+    if parts.len() != 3 || parts.iter().any(|p| p.is_empty()) {
+        anyhow::bail!(CliError::Validation(format!(
+            "`{}` is not a unified message origin (platform:message_type:session_id)",
+            umo
+        )));
+    }
+    Ok(())
+}
+
+/// Read the broadcast targets, bare IDs are groups of `platform`
+fn read_targets(path: &Path, platform: &str) -> anyhow::Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut targets: Vec<String> = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let target = if line.contains(':') {
+            check_umo(line)?;
+            if !line.starts_with(&format!("{}:", platform)) {
+                anyhow::bail!(CliError::Validation(format!(
+                    "{}:{}: `{}` belongs to another platform than {}",
+                    path.display(),
+                    number + 1,
+                    line,
+                    platform
+                )));
+            }
+            line.to_string()
+        } else {
+            format!("{}:GroupMessage:{}", platform, line)
+        };
+        if targets.contains(&target) {
+            vprintln!("Skipping duplicate target {}", target);
+            continue;
+        }
+        targets.push(target);
+    }
+    if targets.is_empty() {
+        anyhow::bail!(CliError::Validation(format!(
+            "{} lists no targets",
+            path.display()
+        )));
+    }
+    Ok(targets)
+}
+
+async fn ensure_platform(api_client: &ApiClient, platform: &str) -> anyhow::Result<()> {
+    let exists = api_client
+        .get_platforms()
+        .await?
+        .iter()
+        .any(|p| p.get("id").and_then(|id| id.as_str()) == Some(platform));
+    if !exists {
+        anyhow::bail!(CliError::NotFound(format!(
+            "Platform `{}` does not exist",
+            platform
+        )));
+    }
+    Ok(())
+}
+
+pub async fn handle_send(session: &str, content: MessageContent) -> anyhow::Result<()> {
+    check_umo(session)?;
+    let (text, image) = load_content(content).await?;
+    let api_client = build_client()?;
+    let ret = api_client
+        .send_message(session, &text, image.as_ref())
+        .await?;
+    vprintln!("{}", ret);
+    output::print_message(&format!("Message sent to {}", session));
+    Ok(())
+}
+
+pub async fn handle_broadcast(
+    platform: &str,
+    groups_file: &Path,
+    content: MessageContent,
+    interval: f64,
+    dry_run: bool,
+    yes: bool,
+) -> anyhow::Result<()> {
+    if !interval.is_finite() || interval < 0.0 {
+        anyhow::bail!(CliError::Validation(
+            "--interval must be a non-negative number of seconds".to_string()
+        ));
+    }
+    let interval = Duration::from_secs_f64(interval);
+    let targets = read_targets(groups_file, platform)?;
+    let (text, image) = load_content(content).await?;
+    let api_client = build_client()?;
+    ensure_platform(&api_client, platform).await?;
+
+    if dry_run {
+        if output::is_json() {
+            return output::print_json(&json!({ "targets": targets }));
+        }
+        for target in &targets {
+            println!("{}", target);
+        }
+        iprintln!("Would send to {} targets", targets.len());
+        return Ok(());
+    }
+    if !yes
+        && !confirm(&format!(
+            "Send the message to {} targets of {}?",
+            targets.len(),
+            platform
+        ))?
+    {
+        output::print_message("Aborted");
+        return Ok(());
+    }
+
+    let mut results = Vec::new();
+    let mut failed = 0;
+    for (i, target) in targets.iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(interval).await;
+        }
+        let result = match api_client.send_message(target, &text, image.as_ref()).await {
+            Ok(_) => {
+                iprintln!("sent   {}", target);
+                json!({ "target": target, "status": "sent" })
+            }
+            Err(e) => {
+                failed += 1;
+                iprintln!("failed {}: {:#}", target, e);
+                json!({ "target": target, "status": "failed", "error": format!("{:#}", e) })
+            }
+        };
+        results.push(result);
+    }
+
+    if output::is_json() {
+        output::print_json(&results)?;
+    }
+    if failed > 0 {
+        anyhow::bail!(CliError::Api(format!(
+            "{} of {} messages failed",
+            failed,
+            targets.len()
+        )));
+    }
+    if !output::is_json() {
+        output::print_message(&format!("Sent to {} targets", targets.len()));
+    }
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+use crate::iprintln;
+
+/// Global JSON output flag state
+static JSON: OnceLock<bool> = OnceLock::new();
+
+/// Initialize the JSON output flag
+pub fn init_json(json: bool) {
+    JSON.set(json).expect("JSON flag already initialized");
+}
+
+/// Check if JSON output mode is enabled
+pub fn is_json() -> bool {
+    *JSON.get().unwrap_or(&false)
+}
+
+/// Set while a command writes a report meant for other tools to stdout
+static STDOUT_RESERVED: AtomicBool = AtomicBool::new(false);
+
+/// Keep info and verbose messages off stdout, which carries a report
+pub fn reserve_stdout() {
+    STDOUT_RESERVED.store(true, Ordering::Relaxed);
+}
+
+/// Whether info and verbose messages go to stderr, in JSON mode or when
+/// stdout carries a report
+pub fn messages_to_stderr() -> bool {
+    is_json() || STDOUT_RESERVED.load(Ordering::Relaxed)
+}
+
+/// Print a command result message, as `{"message": ...}` in JSON mode
+pub fn print_message(message: &str) {
+    if is_json() {
+        println!("{}", serde_json::json!({ "message": message }));
+    } else {
+        iprintln!("{}", message);
+    }
+}
+
+/// Print a value as pretty JSON
+pub fn print_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
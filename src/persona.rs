@@ -0,0 +1,303 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::api::Persona;
+use crate::cli::PersonaFields;
+use crate::error::CliError;
+use crate::utils::{build_client, confirm};
+use crate::{iprintln, output, vprintln};
+
+/// Apply the field options of `persona create`/`persona update`
+async fn apply_fields(fields: PersonaFields, persona: &mut Persona) -> anyhow::Result<()> {
+    if let Some(prompt) = fields.prompt {
+        persona.system_prompt = prompt;
+    }
+    if let Some(file) = fields.prompt_file {
+        persona.system_prompt = tokio::fs::read_to_string(&file)
+            .await
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+    }
+    if !fields.dialogs.is_empty() {
+        persona.begin_dialogs = fields.dialogs;
+    }
+    if fields.all_tools {
+        persona.tools = None;
+    } else if fields.no_tools {
+        persona.tools = Some(Vec::new());
+    } else if !fields.tools.is_empty() {
+        persona.tools = Some(fields.tools);
+    }
+    validate(persona)
+}
+
+fn validate(persona: &Persona) -> anyhow::Result<()> {
+    if persona.persona_id.trim().is_empty() {
+        anyhow::bail!(CliError::Validation(
+            "Persona id must not be empty".to_string()
+        ));
+    }
+    if !persona.begin_dialogs.len().is_multiple_of(2) {
+        anyhow::bail!(CliError::Validation(format!(
+            "Persona `{}`: begin dialogs must alternate user/assistant and come in pairs",
+            persona.persona_id
+        )));
+    }
+    Ok(())
+}
+
+/// Front matter of an exported persona Markdown file
+#[derive(Deserialize, Serialize)]
+struct PersonaMeta {
+    persona_id: String,
+    #[serde(default)]
+    begin_dialogs: Vec<String>,
+    #[serde(default)]
+    tools: Option<Vec<String>>,
+}
+
+/// Render a persona as Markdown with YAML front matter, the body is the prompt
+fn to_markdown(persona: &Persona) -> anyhow::Result<String> {
+    let meta = PersonaMeta {
+        persona_id: persona.persona_id.clone(),
+        begin_dialogs: persona.begin_dialogs.clone(),
+        tools: persona.tools.clone(),
+    };
+    let mut text = format!(
+        "---\n{}---\n{}",
+        serde_yaml::to_string(&meta)?,
+        persona.system_prompt
+    );
+    text.push('\n');
+    Ok(text)
+}
+
+fn from_markdown(text: &str, path: &Path) -> anyhow::Result<Persona> {
+    let invalid = |reason: &str| CliError::Validation(format!("{}: {}", path.display(), reason));
+    let text = text.replace("\r\n", "\n");
+    let rest = text
+        .strip_prefix("---\n")
+        .ok_or_else(|| invalid("missing YAML front matter"))?;
+    let (front, body) = rest
+        .split_once("\n---\n")
+        .ok_or_else(|| invalid("unterminated YAML front matter"))?;
+    let meta: PersonaMeta = serde_yaml::from_str(front).map_err(|e| invalid(&e.to_string()))?;
+    Ok(Persona {
+        persona_id: meta.persona_id,
+        system_prompt: body.strip_suffix('\n').unwrap_or(body).to_string(),
+        begin_dialogs: meta.begin_dialogs,
+        tools: meta.tools,
+    })
+}
+
+fn file_stem(persona_id: &str) -> String {
+    persona_id
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn pretty_print(persona: &Persona) {
+    println!("ID: {}", persona.persona_id);
+    match &persona.tools {
+        None => println!("Tools: all"),
+        Some(tools) if tools.is_empty() => println!("Tools: none"),
+        Some(tools) => println!("Tools: {}", tools.join(", ")),
+    }
+    for pair in persona.begin_dialogs.chunks(2) {
+        println!("User: {}", pair[0]);
+        if let Some(reply) = pair.get(1) {
+            println!("Assistant: {}", reply);
+        }
+    }
+    println!("System prompt:");
+    println!("{}", persona.system_prompt);
+    println!()
+}
+
+pub async fn handle_persona_list() -> anyhow::Result<()> {
+    iprintln!("Fetching persona list...");
+    let api_client = build_client()?;
+    let personas = api_client.get_personas().await?;
+    if output::is_json() {
+        return output::print_json(&personas);
+    }
+    for persona in personas {
+        let summary: String = persona
+            .system_prompt
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .take(60)
+            .collect();
+        println!("{}: {}", persona.persona_id, summary);
+    }
+    Ok(())
+}
+
+pub async fn handle_persona_show(persona_id: &str) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let persona = api_client.get_persona(persona_id).await?;
+    if output::is_json() {
+        output::print_json(&persona)
+    } else {
+        pretty_print(&persona);
+        Ok(())
+    }
+}
+
+pub async fn handle_persona_create(
+    persona_id: String,
+    fields: PersonaFields,
+) -> anyhow::Result<()> {
+    let mut persona = Persona {
+        persona_id,
+        system_prompt: String::new(),
+        begin_dialogs: Vec::new(),
+        tools: None,
+    };
+    apply_fields(fields, &mut persona).await?;
+    let api_client = build_client()?;
+    let ret = api_client.create_persona(&persona).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_persona_update(persona_id: &str, fields: PersonaFields) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let mut persona = api_client.get_persona(persona_id).await?;
+    apply_fields(fields, &mut persona).await?;
+    let ret = api_client.update_persona(&persona).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_persona_delete(persona_id: &str, yes: bool) -> anyhow::Result<()> {
+    if !yes && !confirm(&format!("Delete persona `{}`?", persona_id))? {
+        output::print_message("Aborted");
+        return Ok(());
+    }
+    let api_client = build_client()?;
+    let ret = api_client.delete_persona(persona_id).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_persona_export(dir: &Path) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let personas = api_client.get_personas().await?;
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    // Different ids may sanitize to the same name, and some file systems
+    // ignore case
+    let mut used = HashSet::new();
+    for persona in &personas {
+        let stem = file_stem(&persona.persona_id);
+        let mut name = stem.clone();
+        let mut n = 1;
+        while !used.insert(name.to_lowercase()) {
+            n += 1;
+            name = format!("{}-{}", stem, n);
+        }
+        let path = dir.join(format!("{}.md", name));
+        vprintln!("Writing {}", path.display());
+        tokio::fs::write(&path, to_markdown(persona)?)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    output::print_message(&format!(
+        "Exported {} personas to {}",
+        personas.len(),
+        dir.display()
+    ));
+    Ok(())
+}
+
+/// Read personas from `.md` (front matter + prompt) and `.yaml`/`.yml` files
+async fn read_persona_dir(dir: &Path) -> anyhow::Result<Vec<Persona>> {
+    let mut entries = tokio::fs::read_dir(dir)
+        .await
+        .with_context(|| format!("Failed to read {}", dir.display()))?;
+    let mut paths = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        paths.push(entry.path());
+    }
+    paths.sort();
+
+    let mut personas = Vec::new();
+    for path in paths {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        if !matches!(extension, "md" | "yaml" | "yml") {
+            continue;
+        }
+        let text = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let persona = if extension == "md" {
+            from_markdown(&text, &path)?
+        } else {
+            serde_yaml::from_str(&text)
+                .map_err(|e| CliError::Validation(format!("{}: {}", path.display(), e)))?
+        };
+        validate(&persona)?;
+        personas.push(persona);
+    }
+    Ok(personas)
+}
+
+pub async fn handle_persona_import(dir: &Path, dry_run: bool) -> anyhow::Result<()> {
+    let personas = read_persona_dir(dir).await?;
+    let api_client = build_client()?;
+    let existing: HashMap<String, Persona> = api_client
+        .get_personas()
+        .await?
+        .into_iter()
+        .map(|p| (p.persona_id.clone(), p))
+        .collect();
+
+    let (mut created, mut updated, mut unchanged) = (0, 0, 0);
+    for persona in &personas {
+        match existing.get(&persona.persona_id) {
+            Some(current) if current == persona => {
+                vprintln!("unchanged {}", persona.persona_id);
+                unchanged += 1;
+            }
+            Some(_) => {
+                iprintln!("update {}", persona.persona_id);
+                if !dry_run {
+                    api_client.update_persona(persona).await?;
+                }
+                updated += 1;
+            }
+            None => {
+                iprintln!("create {}", persona.persona_id);
+                if !dry_run {
+                    api_client.create_persona(persona).await?;
+                }
+                created += 1;
+            }
+        }
+    }
+
+    output::print_message(&format!(
+        "{}{} created, {} updated, {} unchanged",
+        if dry_run { "(dry run) " } else { "" },
+        created,
+        updated,
+        unchanged
+    ));
+    Ok(())
+}
@@ -0,0 +1,256 @@
+use std::io::IsTerminal;
+use std::path::Path;
+
+use anyhow::Context;
+use serde_json::{Map, Value};
+
+use crate::api::ApiClient;
+use crate::error::CliError;
+use crate::utils::{build_client, confirm, is_secret_field, mask_secrets, prompt_line};
+use crate::{iprintln, jsonpath, output};
+
+/// Adapter templates and field descriptions from the core config schema
+struct PlatformSchema {
+    templates: Map<String, Value>,
+    items: Value,
+}
+
+impl PlatformSchema {
+    async fn fetch(api_client: &ApiClient) -> anyhow::Result<Self> {
+        let core = api_client.get_core_config("default").await?;
+        let platform = core
+            .metadata
+            .as_object()
+            .and_then(|groups| {
+                groups
+                    .values()
+                    .find_map(|group| group.pointer("/metadata/platform"))
+            })
+            .ok_or_else(|| CliError::Api("Platform schema is missing".to_string()))?;
+        Ok(Self {
+            templates: platform
+                .get("config_template")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default(),
+            items: platform.get("items").cloned().unwrap_or(Value::Null),
+        })
+    }
+
+    /// Find a template by adapter type (e.g. `telegram`) or template name
+    fn template(&self, adapter: &str) -> Option<&Value> {
+        self.templates.get(adapter).or_else(|| {
+            self.templates
+                .values()
+                .find(|t| t.get("type").and_then(Value::as_str) == Some(adapter))
+        })
+    }
+
+    fn description(&self, key: &str) -> Option<&str> {
+        self.items
+            .get(key)?
+            .get("description")
+            .and_then(Value::as_str)
+            .filter(|d| !d.is_empty())
+    }
+}
+
+fn platform_id(config: &Value) -> &str {
+    config.get("id").and_then(Value::as_str).unwrap_or_default()
+}
+
+async fn find_platform(api_client: &ApiClient, id: &str) -> anyhow::Result<Value> {
+    api_client
+        .get_platforms()
+        .await?
+        .into_iter()
+        .find(|p| platform_id(p) == id)
+        .ok_or_else(|| CliError::NotFound(format!("Platform `{}` does not exist", id)).into())
+}
+
+fn is_interactive(no_input: bool) -> bool {
+    !no_input && std::io::stdin().is_terminal()
+}
+
+/// Ask for every field of `config`, keeping the current value on empty input
+fn prompt_fields(config: &mut Value, schema: &PlatformSchema) -> anyhow::Result<()> {
+    let Value::Object(map) = config else {
+        return Ok(());
+    };
+    for (key, value) in map.iter_mut() {
+        // The adapter type is fixed by the template
+        if key == "type" || value.is_object() {
+            continue;
+        }
+        let label = match schema.description(key) {
+            Some(description) => format!("{} ({})", key, description),
+            None => key.clone(),
+        };
+        let raw = if is_secret_field(key) {
+            let shown = if value.as_str().is_some_and(|s| !s.is_empty()) {
+                "keep current"
+            } else {
+                "empty"
+            };
+            rpassword::prompt_password(format!("{} [{}]: ", label, shown))?
+        } else {
+            prompt_line(&format!("{} [{}]: ", label, value))?
+        };
+        if !raw.is_empty() {
+            *value = jsonpath::coerce(&raw, Some(value))?;
+        }
+    }
+    Ok(())
+}
+
+fn apply_assignments(config: &mut Value, set: Vec<(String, String)>) -> anyhow::Result<()> {
+    for (path, raw_value) in set {
+        let new_value = jsonpath::coerce(&raw_value, jsonpath::get(config, &path))?;
+        jsonpath::set(config, &path, new_value)?;
+    }
+    Ok(())
+}
+
+fn pretty_print(config: &Value) {
+    let field = |name: &str| config.get(name).and_then(Value::as_str).unwrap_or("-");
+    println!("ID: {}", field("id"));
+    println!("Type: {}", field("type"));
+    println!(
+        "Enabled: {}",
+        config
+            .get("enable")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    );
+    println!()
+}
+
+pub async fn handle_platform_list() -> anyhow::Result<()> {
+    iprintln!("Fetching platform list...");
+    let api_client = build_client()?;
+    let mut platforms = api_client.get_platforms().await?;
+
+    if output::is_json() {
+        platforms.iter_mut().for_each(mask_secrets);
+        output::print_json(&platforms)?;
+    } else {
+        platforms.iter().for_each(pretty_print);
+    }
+    Ok(())
+}
+
+pub async fn handle_platform_show(id: &str, reveal: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let mut platform = find_platform(&api_client, id).await?;
+    if !reveal {
+        mask_secrets(&mut platform);
+    }
+    output::print_json(&platform)
+}
+
+pub async fn handle_platform_add(
+    adapter: Option<String>,
+    id: Option<String>,
+    set: Vec<(String, String)>,
+    from_file: Option<&Path>,
+    no_input: bool,
+) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+
+    let (mut config, schema) = match from_file {
+        Some(file) => {
+            let content = tokio::fs::read_to_string(file)
+                .await
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let config = serde_json::from_str(&content).map_err(|e| {
+                CliError::Validation(format!("Invalid JSON in {}: {}", file.display(), e))
+            })?;
+            (config, None)
+        }
+        None => {
+            let schema = PlatformSchema::fetch(&api_client).await?;
+            let adapter = match adapter {
+                Some(adapter) => adapter,
+                None if is_interactive(no_input) => {
+                    println!("Available adapters:");
+                    for (name, template) in &schema.templates {
+                        println!(
+                            "  {} ({})",
+                            template.get("type").and_then(Value::as_str).unwrap_or("?"),
+                            name
+                        );
+                    }
+                    prompt_line("Adapter type: ")?
+                }
+                None => anyhow::bail!(CliError::Validation(
+                    "Specify the adapter with --type".to_string()
+                )),
+            };
+            let config = schema.template(&adapter).cloned().ok_or_else(|| {
+                CliError::NotFound(format!("No template for adapter `{}`", adapter))
+            })?;
+            (config, Some(schema))
+        }
+    };
+
+    if let Some(id) = id {
+        config["id"] = Value::String(id);
+    }
+    // Only fill in the template interactively when no field was given
+    let interactive = set.is_empty() && is_interactive(no_input);
+    apply_assignments(&mut config, set)?;
+    if let (Some(schema), true) = (&schema, interactive) {
+        prompt_fields(&mut config, schema)?;
+    }
+
+    if platform_id(&config).is_empty() {
+        anyhow::bail!(CliError::Validation("A platform needs an id".to_string()));
+    }
+    let ret = api_client.create_platform(&config).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_platform_update(
+    id: &str,
+    set: Vec<(String, String)>,
+    no_input: bool,
+) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let mut config = find_platform(&api_client, id).await?;
+    if set.is_empty() {
+        if !is_interactive(no_input) {
+            anyhow::bail!(CliError::Validation(
+                "Nothing to update, pass --set path=value".to_string()
+            ));
+        }
+        let schema = PlatformSchema::fetch(&api_client).await?;
+        prompt_fields(&mut config, &schema)?;
+    } else {
+        apply_assignments(&mut config, set)?;
+    }
+    let ret = api_client.update_platform(id, &config).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_platform_remove(id: &str, yes: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    find_platform(&api_client, id).await?;
+    if !yes && !confirm(&format!("Remove platform `{}`?", id))? {
+        output::print_message("Aborted");
+        return Ok(());
+    }
+    let ret = api_client.delete_platform(id).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_platform_toggle(id: &str, enable: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let mut config = find_platform(&api_client, id).await?;
+    config["enable"] = Value::Bool(enable);
+    let ret = api_client.update_platform(id, &config).await?;
+    output::print_message(&ret);
+    Ok(())
+}
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 #[allow(dead_code)]
 pub struct Plugin {
     pub name: String,
-    #[serde(skip_serializing, skip_deserializing)]
+    #[serde(default, skip_serializing)]
     pub repo: String,
     #[serde(skip_serializing, skip_deserializing)]
     pub author: String,
@@ -19,8 +19,25 @@ pub struct Plugin {
     pub activated: bool,
     #[serde(skip_serializing, skip_deserializing)]
     pub online_version: String,
-    #[serde(skip_serializing, skip_deserializing)]
-    pub handlers: serde_json::Value,
+    #[serde(default, skip_serializing)]
+    pub handlers: Vec<PluginHandler>,
+}
+
+/// An event handler registered by a plugin
+#[derive(Deserialize, Debug, Clone)]
+pub struct PluginHandler {
+    #[serde(default)]
+    pub handler_full_name: String,
+    #[serde(default)]
+    pub handler_name: String,
+    #[serde(default)]
+    pub desc: String,
+    /// Handler kind as shown by the dashboard, e.g. `指令` for a command
+    #[serde(default, rename = "type")]
+    pub kind: String,
+    /// Command name or regex the handler is triggered by
+    #[serde(default)]
+    pub cmd: String,
 }
 
 impl Plugin {
@@ -40,7 +57,7 @@ pub async fn handle_plugin_get() -> anyhow::Result<Vec<Plugin>> {
     Ok(plugins)
 }
 
-pub async fn handle_plugin_install_from_github(git_repo: &String) -> anyhow::Result<String> {
+pub async fn handle_plugin_install_from_github(git_repo: &str) -> anyhow::Result<String> {
     let api_client = build_client()?;
     let ret = api_client.install_remote_plugin(git_repo).await?;
     Ok(ret)
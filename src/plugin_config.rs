@@ -0,0 +1,233 @@
+use std::path::Path;
+
+use anyhow::Context;
+use serde_json::Value;
+
+use crate::api::ApiClient;
+use crate::error::CliError;
+use crate::utils::{build_client, edit_json, review_json_changes};
+use crate::{iprintln, jsonpath, output, schema};
+
+/// A plugin's current config and the schema items describing it
+struct LoadedConfig {
+    config: Value,
+    items: Value,
+}
+
+impl LoadedConfig {
+    async fn fetch(api_client: &ApiClient, plugin_name: &str) -> anyhow::Result<Self> {
+        let plugin_config = api_client.get_plugin_config(plugin_name).await?;
+        if plugin_config.config.is_null() {
+            anyhow::bail!(CliError::NotFound(format!(
+                "Plugin `{}` is not installed or has no configuration",
+                plugin_name
+            )));
+        }
+        let items = plugin_config
+            .metadata
+            .get(plugin_name)
+            .and_then(|m| m.get("items"))
+            .cloned()
+            .unwrap_or_else(|| Value::Object(Default::default()));
+        Ok(Self {
+            config: plugin_config.config,
+            items,
+        })
+    }
+
+    /// Validate a whole config against the schema
+    fn validate(&self, config: &Value) -> Vec<String> {
+        let mut errors = Vec::new();
+        if let (Value::Object(items), Value::Object(map)) = (&self.items, config) {
+            for (key, entry) in items {
+                if let Some(value) = map.get(key) {
+                    schema::validate(entry, value, key, &mut errors);
+                }
+            }
+        }
+        errors
+    }
+
+    fn ensure_valid(&self, config: &Value) -> anyhow::Result<()> {
+        let errors = self.validate(config);
+        if !errors.is_empty() {
+            anyhow::bail!(CliError::Validation(format!(
+                "Invalid config:\n  {}",
+                errors.join("\n  ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Print config values with their schema descriptions and defaults
+fn print_annotated(items: &Value, config: &Value, indent: usize) {
+    let Value::Object(map) = config else {
+        return;
+    };
+    let pad = " ".repeat(indent);
+    for (key, value) in map {
+        let entry = items.get(key);
+        let kind = entry
+            .and_then(|e| e.get("type"))
+            .and_then(Value::as_str)
+            .unwrap_or("?");
+        match (kind, value) {
+            ("object", Value::Object(_)) => println!("{}{} ({}):", pad, key, kind),
+            _ => println!("{}{} ({}) = {}", pad, key, kind, value),
+        }
+        if let Some(entry) = entry {
+            for field in ["description", "hint"] {
+                if let Some(text) = entry.get(field).and_then(Value::as_str) {
+                    if !text.is_empty() {
+                        println!("{}    {}", pad, text);
+                    }
+                }
+            }
+            if let Some(default) = entry.get("default") {
+                println!("{}    default: {}", pad, default);
+            }
+            if let Some(Value::Array(options)) = entry.get("options") {
+                let options: Vec<String> = options.iter().map(Value::to_string).collect();
+                println!("{}    options: {}", pad, options.join(", "));
+            }
+            if kind == "object" {
+                if let Some(children) = entry.get("items") {
+                    print_annotated(children, value, indent + 2);
+                }
+            }
+        }
+    }
+}
+
+pub async fn handle_plugin_config_get(
+    plugin_name: &str,
+    path: Option<String>,
+) -> anyhow::Result<()> {
+    iprintln!("Fetching config of plugin {}...", plugin_name);
+    let api_client = build_client()?;
+    let loaded = LoadedConfig::fetch(&api_client, plugin_name).await?;
+
+    match path {
+        Some(path) => {
+            let value = jsonpath::get(&loaded.config, &path).ok_or_else(|| {
+                CliError::NotFound(format!("Config path `{}` does not exist", path))
+            })?;
+            match value {
+                Value::String(s) if !output::is_json() => println!("{}", s),
+                other => output::print_json(other)?,
+            }
+        }
+        None if output::is_json() => output::print_json(&loaded.config)?,
+        None => print_annotated(&loaded.items, &loaded.config, 0),
+    }
+    Ok(())
+}
+
+pub async fn handle_plugin_config_set(
+    plugin_name: &str,
+    path: Option<String>,
+    raw_value: Option<String>,
+    from_file: Option<&Path>,
+    yes: bool,
+) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let loaded = LoadedConfig::fetch(&api_client, plugin_name).await?;
+    let mut config = loaded.config.clone();
+
+    match (path, raw_value, from_file) {
+        (Some(path), Some(raw_value), None) => {
+            // The schema type wins, the current value may have drifted from it
+            let placeholder = schema::entry(&loaded.items, &path).and_then(schema::placeholder);
+            let current = placeholder
+                .as_ref()
+                .or(jsonpath::get(&config, &path).filter(|v| !v.is_null()));
+            let new_value = jsonpath::coerce(&raw_value, current)?;
+            jsonpath::set(&mut config, &path, new_value)?;
+        }
+        (None, None, Some(file)) => {
+            let content = tokio::fs::read_to_string(file)
+                .await
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let patch: Value = serde_json::from_str(&content).map_err(|e| {
+                CliError::Validation(format!("Invalid JSON in {}: {}", file.display(), e))
+            })?;
+            if !patch.is_object() {
+                anyhow::bail!(CliError::Validation(format!(
+                    "{} must contain a JSON object",
+                    file.display()
+                )));
+            }
+            jsonpath::merge(&mut config, &patch);
+        }
+        _ => anyhow::bail!(CliError::Validation(
+            "Specify either <PATH> <VALUE> or --from-file".to_string()
+        )),
+    }
+
+    loaded.ensure_valid(&config)?;
+    if !review_json_changes(&loaded.config, &config, yes)? {
+        return Ok(());
+    }
+
+    let ret = api_client
+        .update_plugin_config(plugin_name, &config)
+        .await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_plugin_config_edit(plugin_name: &str, yes: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let loaded = LoadedConfig::fetch(&api_client, plugin_name).await?;
+
+    let edited = edit_json(&loaded.config, |edited| loaded.validate(edited)).await?;
+    if !review_json_changes(&loaded.config, &edited, yes)? {
+        return Ok(());
+    }
+
+    let ret = api_client
+        .update_plugin_config(plugin_name, &edited)
+        .await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_plugin_config_reset(
+    plugin_name: &str,
+    path: Option<String>,
+    yes: bool,
+) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let loaded = LoadedConfig::fetch(&api_client, plugin_name).await?;
+    let mut config = loaded.config.clone();
+
+    match path {
+        Some(path) => {
+            let entry = schema::entry(&loaded.items, &path).ok_or_else(|| {
+                CliError::NotFound(format!("Config path `{}` is not in the schema", path))
+            })?;
+            jsonpath::set(&mut config, &path, schema::default_value(entry))?;
+        }
+        None => {
+            let Value::Object(items) = &loaded.items else {
+                anyhow::bail!(CliError::Api("Plugin config schema is missing".to_string()));
+            };
+            config = Value::Object(
+                items
+                    .iter()
+                    .map(|(key, entry)| (key.clone(), schema::default_value(entry)))
+                    .collect(),
+            );
+        }
+    }
+
+    if !review_json_changes(&loaded.config, &config, yes)? {
+        return Ok(());
+    }
+    let ret = api_client
+        .update_plugin_config(plugin_name, &config)
+        .await?;
+    output::print_message(&ret);
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::api::ApiClient;
+use crate::backup::{collect, execute, plan, selected_sections};
+use crate::cli::BackupSection;
+use crate::error::CliError;
+use crate::utils::{build_profile_client, is_secret_field, is_secret_map, mask_all, mask_secrets};
+use crate::{iprintln, output};
+
+/// Sections compared and promoted without `--only`, the core config,
+/// platforms, MCP servers and knowledge bases are usually specific to one
+/// instance
+const DEFAULT_SECTIONS: [BackupSection; 4] = [
+    BackupSection::Providers,
+    BackupSection::Personas,
+    BackupSection::Plugins,
+    BackupSection::PluginConfigs,
+];
+
+#[derive(Serialize)]
+struct FieldChange {
+    path: String,
+    from: Option<Value>,
+    to: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct ItemChange {
+    name: String,
+    fields: Vec<FieldChange>,
+}
+
+#[derive(Serialize)]
+struct SectionDiff {
+    section: &'static str,
+    only_in_from: Vec<String>,
+    only_in_to: Vec<String>,
+    changed: Vec<ItemChange>,
+}
+
+impl SectionDiff {
+    fn is_empty(&self) -> bool {
+        self.only_in_from.is_empty() && self.only_in_to.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn sections(only: &[BackupSection]) -> Vec<BackupSection> {
+    if only.is_empty() {
+        DEFAULT_SECTIONS.to_vec()
+    } else {
+        selected_sections(only)
+    }
+}
+
+fn connect(from: &str, to: &str) -> anyhow::Result<(ApiClient, ApiClient)> {
+    if from == to {
+        anyhow::bail!(CliError::Validation(
+            "--from and --to name the same profile".to_string()
+        ));
+    }
+    Ok((build_profile_client(from)?, build_profile_client(to)?))
+}
+
+/// Key the items of a section by name, dropping fields that always differ
+/// between instances
+fn items(section: BackupSection, value: Value) -> BTreeMap<String, Value> {
+    let key = match section {
+        BackupSection::CoreConfig => return BTreeMap::from([("default".to_string(), value)]),
+        BackupSection::PluginConfigs => {
+            return match value {
+                Value::Object(map) => map.into_iter().collect(),
+                _ => BTreeMap::new(),
+            }
+        }
+        BackupSection::Providers | BackupSection::Platforms => "id",
+        BackupSection::Personas => "persona_id",
+        BackupSection::Mcp | BackupSection::Plugins => "name",
+        BackupSection::Kb => "kb_name",
+    };
+    let Value::Array(list) = value else {
+        return BTreeMap::new();
+    };
+    list.into_iter()
+        .map(|mut item| {
+            let name = item[key].as_str().unwrap_or_default().to_string();
+            if let Value::Object(map) = &mut item {
+                map.remove("repo");
+                map.remove("kb_id");
+            }
+            (name, item)
+        })
+        .collect()
+}
+
+/// Mask `value` when it sits in a credential-like field or anywhere below
+/// headers or environment variables
+fn display(path: &str, value: Option<&Value>) -> Option<Value> {
+    let key = path.rsplit('.').next().unwrap_or(path);
+    let mut value = value?.clone();
+    if path.split('.').any(is_secret_map) {
+        mask_all(&mut value);
+        return Some(value);
+    }
+    if !is_secret_field(key) {
+        return Some(value);
+    }
+    let mut wrapper = json!({ key: value });
+    mask_secrets(&mut wrapper);
+    Some(wrapper[key].take())
+}
+
+/// Compare objects field by field, anything else as a whole
+fn diff_fields(path: &str, from: Option<&Value>, to: Option<&Value>, out: &mut Vec<FieldChange>) {
+    if let (Some(Value::Object(a)), Some(Value::Object(b))) = (from, to) {
+        let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+        for key in keys {
+            let path = if path.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            diff_fields(&path, a.get(key), b.get(key), out);
+        }
+    } else if from != to {
+        out.push(FieldChange {
+            path: path.to_string(),
+            from: display(path, from),
+            to: display(path, to),
+        });
+    }
+}
+
+fn diff_section(section: BackupSection, from: Value, to: Value) -> SectionDiff {
+    let from = items(section, from);
+    let to = items(section, to);
+    let mut diff = SectionDiff {
+        section: section.name(),
+        only_in_from: from
+            .keys()
+            .filter(|k| !to.contains_key(*k))
+            .cloned()
+            .collect(),
+        only_in_to: to
+            .keys()
+            .filter(|k| !from.contains_key(*k))
+            .cloned()
+            .collect(),
+        changed: Vec::new(),
+    };
+    for (name, value) in &from {
+        let Some(other) = to.get(name) else {
+            continue;
+        };
+        let mut fields = Vec::new();
+        diff_fields("", Some(value), Some(other), &mut fields);
+        if !fields.is_empty() {
+            diff.changed.push(ItemChange {
+                name: name.clone(),
+                fields,
+            });
+        }
+    }
+    diff
+}
+
+fn show(value: &Option<Value>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "(unset)".to_string(), Value::to_string)
+}
+
+pub async fn handle_diff(from: &str, to: &str, only: &[BackupSection]) -> anyhow::Result<()> {
+    let (from_client, to_client) = connect(from, to)?;
+    let mut diffs = Vec::new();
+    for section in sections(only) {
+        iprintln!("Comparing {}...", section.name());
+        let (a, b) =
+            tokio::try_join!(collect(&from_client, section), collect(&to_client, section))?;
+        diffs.push(diff_section(section, a, b));
+    }
+    let identical = diffs.iter().all(SectionDiff::is_empty);
+
+    if output::is_json() {
+        return output::print_json(&json!({
+            "from": from,
+            "to": to,
+            "identical": identical,
+            "sections": diffs,
+        }));
+    }
+    if identical {
+        output::print_message(&format!("No differences between {} and {}", from, to));
+        return Ok(());
+    }
+    println!("--- {}", from);
+    println!("+++ {}", to);
+    for diff in diffs.iter().filter(|d| !d.is_empty()) {
+        println!("{}", diff.section);
+        for name in &diff.only_in_from {
+            println!("  - {}", name);
+        }
+        for name in &diff.only_in_to {
+            println!("  + {}", name);
+        }
+        for item in &diff.changed {
+            println!("  ~ {}", item.name);
+            for field in &item.fields {
+                let path = if field.path.is_empty() {
+                    "value"
+                } else {
+                    &field.path
+                };
+                println!(
+                    "      {}: {} -> {}",
+                    path,
+                    show(&field.from),
+                    show(&field.to)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_promote(
+    from: &str,
+    to: &str,
+    only: &[BackupSection],
+    dry_run: bool,
+    yes: bool,
+) -> anyhow::Result<()> {
+    let (source, target) = connect(from, to)?;
+    let mut snapshot = Vec::new();
+    for section in sections(only) {
+        iprintln!("Reading {} from {}...", section.name(), from);
+        snapshot.push((section, collect(&source, section).await?));
+    }
+    iprintln!("Comparing with {}...", to);
+    let plan = plan(&target, snapshot).await?;
+    execute(&target, &plan, dry_run, yes).await
+}
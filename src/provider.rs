@@ -0,0 +1,210 @@
+use std::path::Path;
+
+use anyhow::Context;
+use serde_json::{json, Value};
+
+use crate::api::{ApiClient, PROVIDER_TYPES};
+use crate::cli::ProviderFields;
+use crate::error::CliError;
+use crate::utils::{build_client, confirm, mask_secret, mask_secrets};
+use crate::{iprintln, jsonpath, output};
+
+/// Apply the field options of `provider add`/`provider update` to a config
+fn apply_fields(fields: ProviderFields, config: &mut Value) -> anyhow::Result<()> {
+    if let Some(api_base) = fields.api_base {
+        config["api_base"] = Value::String(api_base);
+    }
+    if let Some(model) = fields.model {
+        if !config["model_config"].is_object() {
+            config["model_config"] = json!({});
+        }
+        config["model_config"]["model"] = Value::String(model);
+    }
+    if !fields.keys.is_empty() {
+        config["key"] = json!(fields.keys);
+    }
+    if !fields.add_keys.is_empty() {
+        match config["key"].as_array_mut() {
+            Some(keys) => keys.extend(fields.add_keys.into_iter().map(Value::String)),
+            None => config["key"] = json!(fields.add_keys),
+        }
+    }
+    for (path, raw_value) in fields.set {
+        let new_value = jsonpath::coerce(&raw_value, jsonpath::get(config, &path))?;
+        jsonpath::set(config, &path, new_value)?;
+    }
+    Ok(())
+}
+
+fn provider_id(config: &Value) -> &str {
+    config.get("id").and_then(Value::as_str).unwrap_or_default()
+}
+
+async fn find_provider(api_client: &ApiClient, id: &str) -> anyhow::Result<Value> {
+    api_client
+        .get_providers(&PROVIDER_TYPES.join(","))
+        .await?
+        .into_iter()
+        .find(|p| provider_id(p) == id)
+        .ok_or_else(|| CliError::NotFound(format!("Provider `{}` does not exist", id)).into())
+}
+
+fn pretty_print(config: &Value) {
+    let field = |name: &str| config.get(name).and_then(Value::as_str).unwrap_or("-");
+    println!("ID: {}", field("id"));
+    println!("Type: {} ({})", field("type"), field("provider_type"));
+    println!(
+        "Enabled: {}",
+        config
+            .get("enable")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    );
+    println!(
+        "Model: {}",
+        config
+            .pointer("/model_config/model")
+            .and_then(Value::as_str)
+            .unwrap_or("-")
+    );
+    println!("API base: {}", field("api_base"));
+    if let Some(keys) = config.get("key").and_then(Value::as_array) {
+        let keys: Vec<String> = keys
+            .iter()
+            .filter_map(Value::as_str)
+            .map(mask_secret)
+            .collect();
+        println!("Keys: {}", keys.join(", "));
+    }
+    println!()
+}
+
+pub async fn handle_provider_list(provider_type: Option<String>) -> anyhow::Result<()> {
+    iprintln!("Fetching provider list...");
+    let api_client = build_client()?;
+    let types = provider_type.unwrap_or_else(|| PROVIDER_TYPES.join(","));
+    let mut providers = api_client.get_providers(&types).await?;
+
+    if output::is_json() {
+        providers.iter_mut().for_each(mask_secrets);
+        output::print_json(&providers)?;
+    } else {
+        providers.iter().for_each(pretty_print);
+    }
+    Ok(())
+}
+
+pub async fn handle_provider_show(id: &str, reveal: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let mut provider = find_provider(&api_client, id).await?;
+    if !reveal {
+        mask_secrets(&mut provider);
+    }
+    output::print_json(&provider)
+}
+
+pub async fn handle_provider_add(
+    id: Option<String>,
+    provider_source: Option<String>,
+    provider_type: String,
+    disabled: bool,
+    from_file: Option<&Path>,
+    fields: ProviderFields,
+) -> anyhow::Result<()> {
+    let mut config = match from_file {
+        Some(file) => {
+            let content = tokio::fs::read_to_string(file)
+                .await
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            serde_json::from_str(&content).map_err(|e| {
+                CliError::Validation(format!("Invalid JSON in {}: {}", file.display(), e))
+            })?
+        }
+        None => json!({
+            "provider_type": provider_type,
+            "enable": !disabled,
+            "key": [],
+            "model_config": {},
+        }),
+    };
+    if let Some(id) = id {
+        config["id"] = Value::String(id);
+    }
+    if let Some(provider_source) = provider_source {
+        config["type"] = Value::String(provider_source);
+    }
+    apply_fields(fields, &mut config)?;
+
+    if provider_id(&config).is_empty() || config.get("type").and_then(Value::as_str).is_none() {
+        anyhow::bail!(CliError::Validation(
+            "A provider needs both an id (--id) and a type (--type)".to_string()
+        ));
+    }
+
+    let api_client = build_client()?;
+    let ret = api_client.create_provider(&config).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_provider_update(id: &str, fields: ProviderFields) -> anyhow::Result<()> {
+    if fields.is_empty() {
+        anyhow::bail!(CliError::Validation(
+            "Nothing to update, pass --api-base, --model, --key, --add-key or --set".to_string()
+        ));
+    }
+    let api_client = build_client()?;
+    let mut config = find_provider(&api_client, id).await?;
+    apply_fields(fields, &mut config)?;
+    let ret = api_client.update_provider(id, &config).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_provider_remove(id: &str, yes: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    find_provider(&api_client, id).await?;
+    if !yes && !confirm(&format!("Remove provider `{}`?", id))? {
+        output::print_message("Aborted");
+        return Ok(());
+    }
+    let ret = api_client.delete_provider(id).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_provider_toggle(id: &str, enable: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let mut config = find_provider(&api_client, id).await?;
+    config["enable"] = Value::Bool(enable);
+    let ret = api_client.update_provider(id, &config).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_provider_test(id: &str) -> anyhow::Result<()> {
+    iprintln!("Testing provider {}...", id);
+    let api_client = build_client()?;
+    let status = api_client.check_provider(id).await?;
+    if status.status != "available" {
+        anyhow::bail!(CliError::Api(format!(
+            "Provider `{}` is {}: {}",
+            status.id,
+            status.status,
+            status.error.unwrap_or_default()
+        )));
+    }
+    output::print_message(&format!("Provider `{}` is available", status.id));
+    Ok(())
+}
+
+pub async fn handle_provider_models(id: &str) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let models = api_client.get_provider_models(id).await?.models;
+    if output::is_json() {
+        output::print_json(&models)?;
+    } else {
+        models.iter().for_each(|model| println!("{}", model));
+    }
+    Ok(())
+}
@@ -0,0 +1,99 @@
+//! Helpers for the dashboard's config schema entries, shaped like
+//! `{type, description, hint, default, options, items}`.
+
+use serde_json::Value;
+
+/// An empty value of the type declared by a schema entry
+pub fn placeholder(entry: &Value) -> Option<Value> {
+    let value = match entry.get("type")?.as_str()? {
+        "bool" => Value::Bool(false),
+        "int" => Value::from(0),
+        "float" => Value::from(0.0),
+        "string" | "text" => Value::String(String::new()),
+        "list" => Value::Array(Vec::new()),
+        "object" | "dict" => Value::Object(Default::default()),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// The default value of a schema entry, objects are built from their items
+pub fn default_value(entry: &Value) -> Value {
+    if let Some(default) = entry.get("default") {
+        return default.clone();
+    }
+    match (
+        entry.get("type").and_then(Value::as_str),
+        entry.get("items"),
+    ) {
+        (Some("object"), Some(Value::Object(items))) => Value::Object(
+            items
+                .iter()
+                .map(|(key, item)| (key.clone(), default_value(item)))
+                .collect(),
+        ),
+        _ => placeholder(entry).unwrap_or(Value::Null),
+    }
+}
+
+/// Look up the schema entry of a dotted path inside a map of items
+pub fn entry<'a>(items: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut keys = path.split('.').filter(|s| !s.is_empty());
+    let mut entry = items.get(keys.next()?)?;
+    for key in keys {
+        let items = entry.get("items")?;
+        entry = match key.parse::<usize>() {
+            // List elements share the `items` schema
+            Ok(_) => items,
+            Err(_) => items.get(key)?,
+        };
+    }
+    Some(entry)
+}
+
+/// Check a value against a schema entry, collecting problems as `path: reason`
+pub fn validate(entry: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let path_name = if path.is_empty() { "<root>" } else { path };
+    let kind = entry.get("type").and_then(Value::as_str).unwrap_or("");
+    let type_ok = match kind {
+        "bool" => value.is_boolean(),
+        "int" => value.is_i64() || value.is_u64(),
+        "float" => value.is_number(),
+        "string" | "text" => value.is_string(),
+        "list" => value.is_array(),
+        "object" | "dict" => value.is_object(),
+        // Unknown types are left to the server
+        _ => true,
+    };
+    if !type_ok {
+        errors.push(format!("{}: expected {}, got {}", path_name, kind, value));
+        return;
+    }
+
+    if let Some(Value::Array(options)) = entry.get("options") {
+        if !options.is_empty() && !options.contains(value) {
+            let options: Vec<String> = options.iter().map(Value::to_string).collect();
+            errors.push(format!(
+                "{}: {} is not one of [{}]",
+                path_name,
+                value,
+                options.join(", ")
+            ));
+        }
+    }
+
+    if let (Some(Value::Object(items)), Value::Object(map)) = (entry.get("items"), value) {
+        if kind == "object" {
+            for (key, item) in items {
+                if let Some(child) = map.get(key) {
+                    let child_path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    validate(item, child, &child_path, errors);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::time::{Duration, Instant};
+
+use indicatif::{ProgressBar, ProgressStyle};
+use serde_json::json;
+
+use crate::api::ApiClient;
+use crate::error::CliError;
+use crate::utils::{build_client, confirm};
+use crate::{iprintln, output, vprintln};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How a restart went, as seen from the client
+pub struct Restart {
+    /// From the restart request until the server answered again
+    pub elapsed: Duration,
+    /// From the first failed poll until the server answered again
+    pub downtime: Duration,
+}
+
+/// Poll the stat endpoint until the server answers with a start time other
+/// than `old_start_time`, failing after `budget`
+pub async fn wait_for_restart(
+    api_client: &ApiClient,
+    old_start_time: u128,
+    budget: Duration,
+) -> anyhow::Result<Restart> {
+    let started = Instant::now();
+    let spinner = if output::is_json() {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new_spinner()
+    };
+    spinner.set_style(
+        ProgressStyle::with_template("{spinner} {msg} ({elapsed})")
+            .expect("valid spinner template"),
+    );
+    spinner.set_message("Waiting for AstrBot to come back");
+    spinner.enable_steady_tick(Duration::from_millis(100));
+
+    let mut down_since: Option<Instant> = None;
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        // A failing poll may take a while because of retries, count from its start
+        let polled_at = Instant::now();
+        match api_client.get_stat().await {
+            Ok(stat) if stat.start_time != old_start_time => {
+                spinner.finish_and_clear();
+                return Ok(Restart {
+                    elapsed: started.elapsed(),
+                    downtime: down_since.map_or(Duration::ZERO, |since| since.elapsed()),
+                });
+            }
+            Ok(_) => vprintln!("Server still running since {}", old_start_time),
+            Err(e) => {
+                vprintln!("Server not ready: {:#}", e);
+                down_since.get_or_insert(polled_at);
+            }
+        }
+        if started.elapsed() >= budget {
+            spinner.finish_and_clear();
+            anyhow::bail!(CliError::Network(format!(
+                "AstrBot did not come back within {} s",
+                budget.as_secs()
+            )));
+        }
+    }
+}
+
+pub async fn handle_restart(yes: bool, wait_timeout: Duration) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let old_start_time = api_client.get_stat().await?.start_time;
+    if !yes && !confirm("Restart AstrBot core?")? {
+        output::print_message("Aborted");
+        return Ok(());
+    }
+
+    iprintln!("Restarting AstrBot...");
+    let ret = api_client.restart_core().await?;
+    vprintln!("{}", ret);
+    let restart = wait_for_restart(&api_client, old_start_time, wait_timeout).await?;
+
+    if output::is_json() {
+        return output::print_json(&json!({
+            "message": "AstrBot restarted",
+            "elapsed_secs": restart.elapsed.as_secs_f64(),
+            "downtime_secs": restart.downtime.as_secs_f64(),
+        }));
+    }
+    output::print_message(&format!(
+        "AstrBot restarted in {:.1} s (down for {:.1} s)",
+        restart.elapsed.as_secs_f64(),
+        restart.downtime.as_secs_f64()
+    ));
+    Ok(())
+}
@@ -0,0 +1,265 @@
+use serde_json::{json, Value};
+
+use crate::api::Session;
+use crate::cli::Toggle;
+use crate::error::CliError;
+use crate::utils::build_client;
+use crate::{iprintln, jsonpath, output};
+
+/// Core config file holding the admin IDs and the whitelist
+const CONF_ID: &str = "default";
+const ADMINS_PATH: &str = "admins_id";
+const WHITELIST_PATH: &str = "platform_settings.id_whitelist";
+const WHITELIST_SWITCH_PATH: &str = "platform_settings.enable_id_white_list";
+
+fn on_off(on: bool) -> &'static str {
+    if on {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+fn print_session(session: &Session) {
+    println!("Session: {}", session.session_id);
+    if !session.session_name.is_empty() {
+        println!("Name: {}", session.session_name);
+    }
+    println!("Platform: {}", session.platform);
+    println!("Type: {}", session.message_type);
+    println!(
+        "Provider: {}",
+        session.chat_provider_id.as_deref().unwrap_or("(default)")
+    );
+    println!(
+        "Persona: {}",
+        session.persona_id.as_deref().unwrap_or("(default)")
+    );
+    println!("LLM: {}", on_off(session.llm_enabled));
+    println!("Enabled: {}", session.session_enabled);
+}
+
+/// Whether `id` names one of `available`, an empty list is not checked
+fn is_available(available: &[Value], keys: &[&str], id: &str) -> bool {
+    available.is_empty()
+        || available
+            .iter()
+            .any(|item| keys.iter().any(|k| item[*k].as_str() == Some(id)))
+}
+
+pub async fn handle_session_list(platform: Option<&str>) -> anyhow::Result<()> {
+    iprintln!("Fetching sessions...");
+    let api_client = build_client()?;
+    let mut sessions: Vec<Session> = api_client
+        .get_sessions()
+        .await?
+        .sessions
+        .into_iter()
+        .filter(|s| platform.is_none_or(|p| s.platform == p))
+        .collect();
+    sessions.sort_by(|a, b| (&a.platform, &a.session_id).cmp(&(&b.platform, &b.session_id)));
+
+    if output::is_json() {
+        return output::print_json(&sessions);
+    }
+    for session in &sessions {
+        print_session(session);
+        println!();
+    }
+    Ok(())
+}
+
+pub async fn handle_session_set(
+    umo: &str,
+    provider: Option<&str>,
+    persona: Option<&str>,
+    llm: Option<Toggle>,
+) -> anyhow::Result<()> {
+    if provider.is_none() && persona.is_none() && llm.is_none() {
+        anyhow::bail!(CliError::Validation(
+            "Nothing to update, pass --provider, --persona or --llm".to_string()
+        ));
+    }
+    let api_client = build_client()?;
+    let list = api_client.get_sessions().await?;
+    if !list.sessions.iter().any(|s| s.session_id == umo) {
+        anyhow::bail!(CliError::NotFound(format!(
+            "Session `{}` does not exist",
+            umo
+        )));
+    }
+    if let Some(provider) = provider {
+        if !is_available(&list.available_chat_providers, &["id"], provider) {
+            anyhow::bail!(CliError::NotFound(format!(
+                "Chat provider `{}` does not exist",
+                provider
+            )));
+        }
+    }
+    if let Some(persona) = persona {
+        if !is_available(&list.available_personas, &["persona_id", "name"], persona) {
+            anyhow::bail!(CliError::NotFound(format!(
+                "Persona `{}` does not exist",
+                persona
+            )));
+        }
+    }
+
+    if let Some(provider) = provider {
+        let ret = api_client.set_session_provider(umo, provider).await?;
+        iprintln!("{}", ret);
+    }
+    if let Some(persona) = persona {
+        let ret = api_client.set_session_persona(umo, persona).await?;
+        iprintln!("{}", ret);
+    }
+    if let Some(llm) = llm {
+        let ret = api_client.set_session_llm(umo, llm.is_on()).await?;
+        iprintln!("{}", ret);
+    }
+    output::print_message(&format!("Session {} updated", umo));
+    Ok(())
+}
+
+/// Read a list of IDs from the core config, numbers are accepted as well
+fn id_list(config: &Value, path: &str) -> Vec<String> {
+    jsonpath::get(config, path)
+        .and_then(Value::as_array)
+        .map(|ids| {
+            ids.iter()
+                .map(|id| match id {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn print_ids(ids: &[String]) -> anyhow::Result<()> {
+    if output::is_json() {
+        return output::print_json(&ids);
+    }
+    for id in ids {
+        println!("{}", id);
+    }
+    Ok(())
+}
+
+async fn handle_id_list(path: &str) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let config = api_client.get_core_config(CONF_ID).await?.config;
+    print_ids(&id_list(&config, path))
+}
+
+/// Add `ids` to or remove them from the list at `path`, IDs already in the
+/// wanted state are skipped
+async fn handle_id_change(path: &str, ids: &[String], add: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let mut config = api_client.get_core_config(CONF_ID).await?.config;
+    let mut list = id_list(&config, path);
+    let mut changed = 0;
+    for id in ids {
+        let present = list.contains(id);
+        if add && !present {
+            list.push(id.clone());
+            changed += 1;
+        } else if !add && present {
+            list.retain(|i| i != id);
+            changed += 1;
+        } else if add {
+            iprintln!("{} is already listed", id);
+        } else {
+            iprintln!("{} is not listed", id);
+        }
+    }
+    if changed == 0 {
+        if add {
+            output::print_message("Nothing to add");
+            return Ok(());
+        }
+        anyhow::bail!(CliError::NotFound("None of the IDs are listed".to_string()));
+    }
+
+    jsonpath::set(&mut config, path, json!(list))?;
+    let ret = api_client.update_core_config(CONF_ID, &config).await?;
+    iprintln!("{}", ret);
+    let verb = if add { "Added" } else { "Removed" };
+    output::print_message(&format!("{} {} IDs", verb, changed));
+    Ok(())
+}
+
+pub async fn handle_admin_list() -> anyhow::Result<()> {
+    handle_id_list(ADMINS_PATH).await
+}
+
+pub async fn handle_admin_change(ids: &[String], add: bool) -> anyhow::Result<()> {
+    handle_id_change(ADMINS_PATH, ids, add).await
+}
+
+pub async fn handle_whitelist_list() -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let config = api_client.get_core_config(CONF_ID).await?.config;
+    let enabled = jsonpath::get(&config, WHITELIST_SWITCH_PATH)
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let ids = id_list(&config, WHITELIST_PATH);
+
+    if output::is_json() {
+        return output::print_json(&json!({ "enabled": enabled, "ids": ids }));
+    }
+    println!("Whitelist: {}", on_off(enabled));
+    for id in &ids {
+        println!("{}", id);
+    }
+    Ok(())
+}
+
+pub async fn handle_whitelist_change(ids: &[String], add: bool) -> anyhow::Result<()> {
+    handle_id_change(WHITELIST_PATH, ids, add).await
+}
+
+pub async fn handle_whitelist_toggle(on: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let mut config = api_client.get_core_config(CONF_ID).await?.config;
+    jsonpath::set(&mut config, WHITELIST_SWITCH_PATH, json!(on))?;
+    let ret = api_client.update_core_config(CONF_ID, &config).await?;
+    iprintln!("{}", ret);
+    output::print_message(&format!("Whitelist turned {}", on_off(on)));
+    Ok(())
+}
+
+pub async fn handle_blacklist_list() -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let ids: Vec<String> = api_client
+        .get_sessions()
+        .await?
+        .sessions
+        .into_iter()
+        .filter(|s| !s.session_enabled)
+        .map(|s| s.session_id)
+        .collect();
+    print_ids(&ids)
+}
+
+/// Blacklisting a session disables it, the bot then ignores its messages
+pub async fn handle_blacklist_change(umos: &[String], add: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let sessions = api_client.get_sessions().await?.sessions;
+    if let Some(unknown) = umos
+        .iter()
+        .find(|umo| !sessions.iter().any(|s| &s.session_id == *umo))
+    {
+        anyhow::bail!(CliError::NotFound(format!(
+            "Session `{}` does not exist",
+            unknown
+        )));
+    }
+    for umo in umos {
+        let ret = api_client.set_session_enabled(umo, !add).await?;
+        iprintln!("{}: {}", umo, ret);
+    }
+    let verb = if add { "Blacklisted" } else { "Unblacklisted" };
+    output::print_message(&format!("{} {} sessions", verb, umos.len()));
+    Ok(())
+}
@@ -5,26 +5,26 @@ use crate::{
     utils::{build_client, strf_timestamp},
 };
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Platform {
     pub name: String,
     pub count: u128,
     pub timestamp: f64,
 }
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct RunningStat {
     pub hours: u32,
     pub minutes: u32,
     pub seconds: u32,
 }
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct MemoryStat {
     pub process: u32,
     pub system: u32,
 }
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Stat {
     pub platform: Vec<Platform>,
     pub message_count: u128,
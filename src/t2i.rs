@@ -0,0 +1,245 @@
+use std::collections::BTreeSet;
+use std::io::{self, Read};
+use std::path::Path;
+
+use anyhow::Context;
+use serde_json::Value;
+
+use crate::api::{ApiClient, DEFAULT_T2I_ENDPOINT};
+use crate::error::CliError;
+use crate::utils::{build_client, confirm};
+use crate::{iprintln, jsonpath, output, vprintln};
+
+/// Core config file holding the renderer settings
+const CONF_ID: &str = "default";
+
+/// Whether a template name can be used as a file name as is
+fn is_file_safe(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !name.starts_with('.')
+}
+
+async fn ensure_template(api_client: &ApiClient, name: &str) -> anyhow::Result<()> {
+    if api_client
+        .get_t2i_templates()
+        .await?
+        .iter()
+        .any(|t| t.name == name)
+    {
+        Ok(())
+    } else {
+        anyhow::bail!(CliError::NotFound(format!(
+            "Template `{}` does not exist",
+            name
+        )))
+    }
+}
+
+pub async fn handle_template_list() -> anyhow::Result<()> {
+    iprintln!("Fetching templates...");
+    let api_client = build_client()?;
+    let templates = api_client.get_t2i_templates().await?;
+
+    if output::is_json() {
+        return output::print_json(&templates);
+    }
+    for template in &templates {
+        println!("Name: {}", template.name);
+        println!("Active: {}", template.is_active);
+        println!();
+    }
+    Ok(())
+}
+
+pub async fn handle_template_pull(dir: &Path) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let templates = api_client.get_t2i_templates().await?;
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let mut pulled = 0;
+    for template in &templates {
+        if !is_file_safe(&template.name) {
+            iprintln!(
+                "Skipping `{}`, the name is not a valid file name",
+                template.name
+            );
+            continue;
+        }
+        let content = api_client.get_t2i_template(&template.name).await?;
+        let path = dir.join(format!("{}.html", template.name));
+        vprintln!("Writing {}", path.display());
+        tokio::fs::write(&path, content)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        pulled += 1;
+    }
+    output::print_message(&format!("Pulled {} templates to {}", pulled, dir.display()));
+    Ok(())
+}
+
+pub async fn handle_template_push(dir: &Path, dry_run: bool) -> anyhow::Result<()> {
+    let mut entries = tokio::fs::read_dir(dir)
+        .await
+        .with_context(|| format!("Failed to read {}", dir.display()))?;
+    let mut paths = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) == Some("html") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let api_client = build_client()?;
+    let existing: BTreeSet<String> = api_client
+        .get_t2i_templates()
+        .await?
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+
+    let (mut created, mut updated, mut unchanged) = (0, 0, 0);
+    for path in &paths {
+        let Some(name) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|s| !s.is_empty())
+        else {
+            iprintln!(
+                "Skipping {}, the file name is not valid UTF-8",
+                path.display()
+            );
+            continue;
+        };
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if !existing.contains(name) {
+            iprintln!("create {}", name);
+            if !dry_run {
+                api_client.create_t2i_template(name, &content).await?;
+            }
+            created += 1;
+        } else if api_client.get_t2i_template(name).await? == content {
+            vprintln!("unchanged {}", name);
+            unchanged += 1;
+        } else {
+            iprintln!("update {}", name);
+            if !dry_run {
+                api_client.update_t2i_template(name, &content).await?;
+            }
+            updated += 1;
+        }
+    }
+
+    output::print_message(&format!(
+        "{}{} created, {} updated, {} unchanged",
+        if dry_run { "(dry run) " } else { "" },
+        created,
+        updated,
+        unchanged
+    ));
+    Ok(())
+}
+
+pub async fn handle_template_set_active(name: &str) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    ensure_template(&api_client, name).await?;
+    let ret = api_client.set_active_t2i_template(name).await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_template_reset(yes: bool) -> anyhow::Result<()> {
+    if !yes && !confirm("Restore the built-in text-to-image template and make it active?")? {
+        output::print_message("Aborted");
+        return Ok(());
+    }
+    let api_client = build_client()?;
+    let ret = api_client.reset_t2i_template().await?;
+    output::print_message(&ret);
+    Ok(())
+}
+
+pub async fn handle_render(
+    template: &str,
+    text: &Path,
+    output_file: &Path,
+    endpoint: Option<String>,
+) -> anyhow::Result<()> {
+    let image_type = match output_file
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("png") => "png",
+        Some("jpg" | "jpeg") => "jpeg",
+        _ => anyhow::bail!(CliError::Validation(format!(
+            "Cannot tell the image format of {}, use a .png or .jpg file",
+            output_file.display()
+        ))),
+    };
+    let text = if text == Path::new("-") {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        tokio::fs::read_to_string(text)
+            .await
+            .with_context(|| format!("Failed to read {}", text.display()))?
+    };
+
+    let api_client = build_client()?;
+    // A local file wins so designers can try changes before pushing them
+    let template = if Path::new(template).is_file() {
+        tokio::fs::read_to_string(template)
+            .await
+            .with_context(|| format!("Failed to read {}", template))?
+    } else {
+        ensure_template(&api_client, template).await?;
+        api_client.get_t2i_template(template).await?
+    };
+    let endpoint = match endpoint {
+        Some(endpoint) => endpoint,
+        None => {
+            let config = api_client.get_core_config(CONF_ID).await?.config;
+            if jsonpath::get(&config, "t2i_strategy").and_then(Value::as_str) == Some("local") {
+                anyhow::bail!(CliError::Validation(
+                    "The server renders images locally without HTML templates, pass --endpoint to use a rendering service".to_string()
+                ));
+            }
+            jsonpath::get(&config, "t2i_endpoint")
+                .and_then(Value::as_str)
+                .filter(|e| !e.is_empty())
+                .unwrap_or(DEFAULT_T2I_ENDPOINT)
+                .to_string()
+        }
+    };
+    let version = match api_client.check_update().await {
+        Ok((status, _)) => status.version,
+        Err(e) => {
+            vprintln!("AstrBot version unavailable: {:#}", e);
+            String::new()
+        }
+    };
+
+    iprintln!("Rendering with {}...", endpoint);
+    let image = api_client
+        .render_t2i(&endpoint, &template, &text, &version, image_type)
+        .await?;
+    tokio::fs::write(output_file, &image)
+        .await
+        .with_context(|| format!("Failed to write {}", output_file.display()))?;
+    output::print_message(&format!(
+        "Rendered {} ({} bytes)",
+        output_file.display(),
+        image.len()
+    ));
+    Ok(())
+}
@@ -0,0 +1,420 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use futures::stream::{self, StreamExt};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::api::{ApiClient, ChatMessage};
+use crate::chat::exchange;
+use crate::cli::ReportFormat;
+use crate::error::CliError;
+use crate::utils::build_client;
+use crate::{iprintln, jsonpath, output};
+
+/// A test file: chat scenarios run against the web chat
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestFile {
+    /// Chat provider used by every case unless the case sets its own
+    #[serde(default)]
+    provider: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    /// Seconds to wait for each reply
+    #[serde(default = "default_timeout")]
+    timeout: u64,
+    cases: Vec<TestCase>,
+}
+
+fn default_timeout() -> u64 {
+    120
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestCase {
+    name: String,
+    #[serde(default)]
+    skip: bool,
+    #[serde(default)]
+    provider: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    steps: Vec<Step>,
+}
+
+/// One message of a case and what its reply must look like
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Step {
+    send: String,
+    #[serde(default)]
+    expect: Expect,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Expect {
+    #[serde(default, deserialize_with = "one_or_many")]
+    contains: Vec<String>,
+    #[serde(default, deserialize_with = "one_or_many")]
+    not_contains: Vec<String>,
+    #[serde(default, deserialize_with = "regexes")]
+    regex: Vec<Regex>,
+    /// Assertions on the reply parsed as JSON
+    #[serde(default, deserialize_with = "one_or_many")]
+    json: Vec<JsonExpect>,
+    #[serde(default)]
+    max_latency_ms: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonExpect {
+    path: String,
+    /// Expected value, without it the path only has to exist
+    #[serde(default)]
+    equals: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(item) => vec![item],
+        OneOrMany::Many(items) => items,
+    })
+}
+
+fn regexes<'de, D>(deserializer: D) -> Result<Vec<Regex>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    one_or_many::<D, String>(deserializer)?
+        .iter()
+        .map(|pattern| Regex::new(pattern).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Serialize)]
+struct CaseResult {
+    name: String,
+    status: Status,
+    duration_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<String>,
+}
+
+/// Check a reply against a step's expectations, returning what did not hold
+fn check(expect: &Expect, reply: &str, latency: Duration) -> Vec<String> {
+    let mut failures = Vec::new();
+    for needle in &expect.contains {
+        if !reply.contains(needle.as_str()) {
+            failures.push(format!("reply does not contain `{}`", needle));
+        }
+    }
+    for needle in &expect.not_contains {
+        if reply.contains(needle.as_str()) {
+            failures.push(format!("reply contains `{}`", needle));
+        }
+    }
+    for regex in &expect.regex {
+        if !regex.is_match(reply) {
+            failures.push(format!("reply does not match /{}/", regex));
+        }
+    }
+    if !expect.json.is_empty() {
+        match serde_json::from_str::<Value>(reply.trim()) {
+            Ok(value) => {
+                for assertion in &expect.json {
+                    match (jsonpath::get(&value, &assertion.path), &assertion.equals) {
+                        (None, _) => {
+                            failures.push(format!("JSON path `{}` is missing", assertion.path))
+                        }
+                        (Some(actual), Some(expected)) if actual != expected => {
+                            failures.push(format!(
+                                "JSON path `{}` is {}, expected {}",
+                                assertion.path, actual, expected
+                            ))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Err(e) => failures.push(format!("reply is not JSON: {}", e)),
+        }
+    }
+    if let Some(budget) = expect.max_latency_ms {
+        if latency.as_millis() > u128::from(budget) {
+            failures.push(format!(
+                "reply took {} ms, over the {} ms budget",
+                latency.as_millis(),
+                budget
+            ));
+        }
+    }
+    failures
+}
+
+fn snippet(text: &str) -> String {
+    let mut s: String = text.chars().take(200).collect();
+    if s.len() < text.len() {
+        s.push('…');
+    }
+    s
+}
+
+/// Run the steps of a case in a session of its own
+async fn run_case(api_client: &ApiClient, file: &TestFile, case: &TestCase) -> CaseResult {
+    let started = Instant::now();
+    let failure = if case.skip {
+        None
+    } else {
+        run_steps(api_client, file, case).await.err()
+    };
+    CaseResult {
+        name: case.name.clone(),
+        status: match (&failure, case.skip) {
+            (_, true) => Status::Skipped,
+            (Some(_), _) => Status::Failed,
+            (None, _) => Status::Passed,
+        },
+        duration_ms: started.elapsed().as_millis(),
+        failure,
+    }
+}
+
+async fn run_steps(api_client: &ApiClient, file: &TestFile, case: &TestCase) -> Result<(), String> {
+    let session_id = api_client
+        .new_chat_session()
+        .await
+        .map_err(|e| format!("cannot start a session: {:#}", e))?;
+    for (i, step) in case.steps.iter().enumerate() {
+        let message = ChatMessage {
+            session_id: session_id.clone(),
+            message: step.send.clone(),
+            selected_provider: case.provider.clone().or_else(|| file.provider.clone()),
+            selected_model: case.model.clone().or_else(|| file.model.clone()),
+            ..Default::default()
+        };
+        let started = Instant::now();
+        let reply = tokio::time::timeout(
+            Duration::from_secs(file.timeout),
+            exchange(api_client, &message, false),
+        )
+        .await
+        .map_err(|_| format!("step {}: no reply within {} s", i + 1, file.timeout))?
+        .map_err(|e| format!("step {}: {:#}", i + 1, e))?;
+        let failures = check(&step.expect, &reply.text, started.elapsed());
+        if !failures.is_empty() {
+            return Err(format!(
+                "step {} (`{}`): {}; reply: {}",
+                i + 1,
+                snippet(&step.send),
+                failures.join(", "),
+                snippet(&reply.text)
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn print_result(result: &CaseResult) {
+    match result.status {
+        Status::Passed => println!("ok   {} ({} ms)", result.name, result.duration_ms),
+        Status::Skipped => println!("skip {}", result.name),
+        Status::Failed => {
+            println!("FAIL {} ({} ms)", result.name, result.duration_ms);
+            if let Some(failure) = &result.failure {
+                println!("     {}", failure);
+            }
+        }
+    }
+}
+
+fn tap_report(results: &[CaseResult]) -> String {
+    let mut out = format!("TAP version 13\n1..{}\n", results.len());
+    for (i, result) in results.iter().enumerate() {
+        let line = match result.status {
+            Status::Passed => format!("ok {} - {}\n", i + 1, result.name),
+            Status::Skipped => format!("ok {} - {} # SKIP\n", i + 1, result.name),
+            Status::Failed => format!("not ok {} - {}\n", i + 1, result.name),
+        };
+        out.push_str(&line);
+        if let Some(failure) = &result.failure {
+            out.push_str("  ---\n");
+            out.push_str(&format!("  message: {}\n", Value::String(failure.clone())));
+            out.push_str(&format!("  duration_ms: {}\n", result.duration_ms));
+            out.push_str("  ...\n");
+        }
+    }
+    out
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn junit_report(suite: &str, results: &[CaseResult]) -> String {
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    let seconds = |ms: u128| format!("{:.3}", ms as f64 / 1000.0);
+    let total_ms: u128 = results.iter().map(|r| r.duration_ms).sum();
+    let suite = xml_escape(suite);
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+        results.len(),
+        count(Status::Failed),
+        count(Status::Skipped),
+        seconds(total_ms)
+    ));
+    out.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+        suite,
+        results.len(),
+        count(Status::Failed),
+        count(Status::Skipped),
+        seconds(total_ms)
+    ));
+    for result in results {
+        out.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\" time=\"{}\"",
+            suite,
+            xml_escape(&result.name),
+            seconds(result.duration_ms)
+        ));
+        match (result.status, &result.failure) {
+            (Status::Failed, Some(failure)) => {
+                let failure = xml_escape(failure);
+                out.push_str(&format!(
+                    ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+                    failure, failure
+                ));
+            }
+            (Status::Skipped, _) => out.push_str(">\n      <skipped/>\n    </testcase>\n"),
+            _ => out.push_str("/>\n"),
+        }
+    }
+    out.push_str("  </testsuite>\n</testsuites>\n");
+    out
+}
+
+pub async fn handle_test_run(
+    file: &Path,
+    jobs: usize,
+    filter: Option<&str>,
+    format: ReportFormat,
+    output_file: Option<&Path>,
+) -> anyhow::Result<()> {
+    if format == ReportFormat::Text && output_file.is_some() {
+        anyhow::bail!(CliError::Validation(
+            "--output needs --format tap or junit".to_string()
+        ));
+    }
+    if format != ReportFormat::Text && output_file.is_none() && output::is_json() {
+        anyhow::bail!(CliError::Validation(
+            "--json needs --output when --format is tap or junit".to_string()
+        ));
+    }
+    let content = tokio::fs::read_to_string(file)
+        .await
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let mut tests: TestFile = serde_yaml::from_str(&content).map_err(|e| {
+        CliError::Validation(format!("Invalid test file {}: {}", file.display(), e))
+    })?;
+    if let Some(filter) = filter {
+        tests.cases.retain(|c| c.name.contains(filter));
+    }
+    if tests.cases.is_empty() {
+        anyhow::bail!(CliError::Validation("No test case to run".to_string()));
+    }
+
+    if format != ReportFormat::Text && output_file.is_none() {
+        output::reserve_stdout();
+    }
+
+    let api_client = build_client()?;
+    iprintln!(
+        "Running {} cases from {} ({} at a time)...",
+        tests.cases.len(),
+        file.display(),
+        jobs
+    );
+    // Print text results as they complete unless the report goes to stdout
+    let live = !output::is_json() && (format == ReportFormat::Text || output_file.is_some());
+    let results: Vec<CaseResult> = stream::iter(&tests.cases)
+        .map(|case| run_case(&api_client, &tests, case))
+        .buffered(jobs.max(1))
+        .inspect(|result| {
+            if live {
+                print_result(result);
+            }
+        })
+        .collect()
+        .await;
+
+    let suite = file
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "astrbot".to_string());
+    let report = match format {
+        ReportFormat::Text => None,
+        ReportFormat::Tap => Some(tap_report(&results)),
+        ReportFormat::Junit => Some(junit_report(&suite, &results)),
+    };
+    match (report, output_file) {
+        (Some(report), Some(path)) => {
+            tokio::fs::write(path, report)
+                .await
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            iprintln!("Report written to {}", path.display());
+        }
+        (Some(report), None) => print!("{}", report),
+        (None, _) => {}
+    }
+    if output::is_json() {
+        output::print_json(&results)?;
+    }
+
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    let failed = count(Status::Failed);
+    iprintln!(
+        "{} passed, {} failed, {} skipped",
+        count(Status::Passed),
+        failed,
+        count(Status::Skipped)
+    );
+    if failed > 0 {
+        anyhow::bail!(CliError::Api(format!(
+            "{} of {} cases failed",
+            failed,
+            results.len()
+        )));
+    }
+    Ok(())
+}
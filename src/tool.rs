@@ -0,0 +1,65 @@
+use crate::api::{ApiClient, FunctionTool};
+use crate::error::CliError;
+use crate::utils::build_client;
+use crate::{iprintln, output};
+
+async fn find_tool(api_client: &ApiClient, name: &str) -> anyhow::Result<FunctionTool> {
+    api_client
+        .get_tools()
+        .await?
+        .into_iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| CliError::NotFound(format!("Tool `{}` does not exist", name)).into())
+}
+
+fn origin(tool: &FunctionTool) -> String {
+    match (tool.origin.as_str(), tool.origin_name.as_str()) {
+        ("", _) => "unknown".to_string(),
+        (kind, "") => kind.to_string(),
+        (kind, name) => format!("{} ({})", kind, name),
+    }
+}
+
+fn print_summary(tool: &FunctionTool) {
+    println!("Name: {}", tool.name);
+    println!("Description: {}", tool.description);
+    println!("Origin: {}", origin(tool));
+    println!("Enabled: {}", tool.active);
+}
+
+pub async fn handle_tool_list() -> anyhow::Result<()> {
+    iprintln!("Fetching tool list...");
+    let api_client = build_client()?;
+    let tools = api_client.get_tools().await?;
+
+    if output::is_json() {
+        output::print_json(&tools)?;
+    } else {
+        for tool in &tools {
+            print_summary(tool);
+            println!();
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_tool_show(name: &str) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let tool = find_tool(&api_client, name).await?;
+
+    if output::is_json() {
+        return output::print_json(&tool);
+    }
+    print_summary(&tool);
+    println!("Parameters:");
+    println!("{}", serde_json::to_string_pretty(&tool.parameters)?);
+    Ok(())
+}
+
+pub async fn handle_tool_toggle(name: &str, activate: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    find_tool(&api_client, name).await?;
+    let ret = api_client.toggle_tool(name, activate).await?;
+    output::print_message(&ret);
+    Ok(())
+}
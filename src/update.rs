@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::api::Release;
+use crate::error::CliError;
+use crate::server::wait_for_restart;
+use crate::utils::{build_client, confirm};
+use crate::{iprintln, output, vprintln};
+
+fn same_version(a: &str, b: &str) -> bool {
+    a.trim().trim_start_matches('v') == b.trim().trim_start_matches('v')
+}
+
+pub async fn handle_update_check() -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    iprintln!("Checking for updates...");
+    let (status, message) = api_client.check_update().await?;
+    vprintln!("{}", message);
+    // The release list comes from GitHub through the server, which may be offline
+    let releases = match api_client.get_releases().await {
+        Ok(releases) => releases,
+        Err(e) => {
+            iprintln!("Changelog unavailable: {:#}", e);
+            Vec::new()
+        }
+    };
+    let newer: Vec<&Release> = releases
+        .iter()
+        .take_while(|r| !same_version(r.tag(), &status.version))
+        .collect();
+    let latest = releases
+        .first()
+        .map(|r| r.tag().to_string())
+        .unwrap_or_else(|| status.version.clone());
+
+    if output::is_json() {
+        return output::print_json(&json!({
+            "current": status.version,
+            "latest": latest,
+            "has_new_version": status.has_new_version,
+            "dashboard_version": status.dashboard_version,
+            "dashboard_has_new_version": status.dashboard_has_new_version,
+            "changelog": newer,
+        }));
+    }
+
+    println!("Current version: {}", status.version);
+    if status.has_new_version {
+        println!("Latest version: {} (update available)", latest);
+    } else {
+        println!("Latest version: {} (up to date)", latest);
+    }
+    if let Some(dashboard) = &status.dashboard_version {
+        let state = if status.dashboard_has_new_version {
+            "update available"
+        } else {
+            "up to date"
+        };
+        println!("Dashboard version: {} ({})", dashboard, state);
+    }
+    if status.has_new_version && !newer.is_empty() {
+        println!();
+        println!("Changelog:");
+        for release in newer {
+            println!("## {} ({})", release.tag(), release.published_at);
+            println!("{}", release.body.trim());
+            println!();
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_update_apply(
+    version: Option<String>,
+    yes: bool,
+    no_wait: bool,
+    wait_timeout: Duration,
+) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let (status, _) = api_client.check_update().await?;
+    let current = status.version;
+    match &version {
+        Some(version) if same_version(version, &current) => {
+            output::print_message(&format!("AstrBot is already running {}", current));
+            return Ok(());
+        }
+        None if !status.has_new_version => {
+            output::print_message(&format!("AstrBot {} is up to date", current));
+            return Ok(());
+        }
+        _ => {}
+    }
+    let target = version.as_deref().unwrap_or("latest");
+    if !yes
+        && !confirm(&format!(
+            "Update AstrBot {} to {}? The server restarts afterwards.",
+            current, target
+        ))?
+    {
+        output::print_message("Aborted");
+        return Ok(());
+    }
+
+    let old_start_time = api_client.get_stat().await?.start_time;
+    iprintln!("Updating AstrBot {} to {}...", current, target);
+    let ret = api_client.update_core(target).await?;
+    if no_wait {
+        output::print_message(&ret);
+        return Ok(());
+    }
+    iprintln!("{}", ret);
+
+    let restart = wait_for_restart(&api_client, old_start_time, wait_timeout).await?;
+    let (status, _) = api_client.check_update().await?;
+    let updated = match &version {
+        Some(version) => same_version(&status.version, version),
+        None => !same_version(&status.version, &current),
+    };
+    if !updated {
+        anyhow::bail!(CliError::Api(format!(
+            "AstrBot came back with version {}, expected {}",
+            status.version, target
+        )));
+    }
+    output::print_message(&format!(
+        "AstrBot updated from {} to {} in {} s (down for {} s)",
+        current,
+        status.version,
+        restart.elapsed.as_secs(),
+        restart.downtime.as_secs()
+    ));
+    Ok(())
+}
+
+pub async fn handle_update_dashboard(force: bool) -> anyhow::Result<()> {
+    let api_client = build_client()?;
+    let (status, _) = api_client.check_update().await?;
+    if !status.dashboard_has_new_version && !force {
+        output::print_message(&format!(
+            "Dashboard {} is up to date",
+            status.dashboard_version.as_deref().unwrap_or("")
+        ));
+        return Ok(());
+    }
+    iprintln!("Updating the dashboard...");
+    let ret = api_client.update_dashboard().await?;
+    output::print_message(&ret);
+    Ok(())
+}
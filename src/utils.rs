@@ -1,12 +1,17 @@
 use std::env;
+use std::fs;
+use std::io::{self, Write};
 use std::path::PathBuf;
 
 use anyhow::Context;
 use chrono::{TimeZone, Utc};
+use similar::{ChangeTag, TextDiff};
 use tokio::process::Command;
 
 use crate::api::ApiClient;
-use crate::config::ConfigManager;
+use crate::config::{Config, ConfigManager};
+use crate::error::CliError;
+use crate::output;
 use crate::vprintln;
 
 pub async fn create_git_archive() -> anyhow::Result<String> {
@@ -55,13 +60,251 @@ pub async fn create_git_archive() -> anyhow::Result<String> {
 }
 
 pub fn build_client() -> anyhow::Result<ApiClient> {
-    let credentials = ConfigManager::load_credentials().with_context(|| {
-        "Failed to load credentials. Check that the config file exists and is valid, or sign in again."
-    })?;
-    Ok(ApiClient::new(credentials.server_url, credentials.token))
+    let credentials = load_credentials()?;
+    ApiClient::new(&credentials)
+}
+
+/// Load the active profile, reporting failures as config errors
+pub fn load_credentials() -> anyhow::Result<Config> {
+    ConfigManager::load_credentials().map_err(|e| {
+        CliError::Config(format!(
+            "Failed to load credentials: {:#}. Check that the config file exists and is valid, or sign in again.",
+            e
+        ))
+        .into()
+    })
+}
+
+/// Build a client for a profile other than the active one
+pub fn build_profile_client(profile: &str) -> anyhow::Result<ApiClient> {
+    let credentials = ConfigManager::load_profile(profile)
+        .map_err(|e| CliError::Config(format!("Failed to load profile `{}`: {:#}", profile, e)))?;
+    ApiClient::new(&credentials)
 }
 
 pub fn strf_timestamp(secs: i64) -> anyhow::Result<String> {
     let dt_utc = Utc.timestamp_opt(secs, 0).unwrap();
     Ok(dt_utc.format("%Y-%m-%d %H:%M:%S").to_string())
 }
+
+/// Shorten a secret to a recognizable prefix, short secrets are hidden entirely
+pub fn mask_secret(secret: &str) -> String {
+    if secret.chars().count() <= 8 {
+        return "********".to_string();
+    }
+    let mut s: String = secret.chars().take(8).collect();
+    s.push('…');
+    s
+}
+
+/// Whether a config field name looks like it holds a credential
+pub fn is_secret_field(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.ends_with("key")
+        || name.ends_with("token")
+        || name.contains("secret")
+        || name.contains("password")
+}
+
+/// Whether every value under a field may be a credential, e.g. an MCP
+/// server's `headers.Authorization` or `env` API keys
+pub fn is_secret_map(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name == "env" || name.ends_with("headers")
+}
+
+/// Mask every string below `value`
+pub fn mask_all(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(s) if !s.is_empty() => *s = mask_secret(s),
+        serde_json::Value::Object(map) => map.values_mut().for_each(mask_all),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(mask_all),
+        _ => {}
+    }
+}
+
+/// Mask every credential-like field of a JSON value in place
+pub fn mask_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (name, field) in map.iter_mut() {
+                if is_secret_map(name) {
+                    mask_all(field);
+                    continue;
+                }
+                if !is_secret_field(name) {
+                    mask_secrets(field);
+                    continue;
+                }
+                match field {
+                    serde_json::Value::String(s) if !s.is_empty() => *s = mask_secret(s),
+                    serde_json::Value::Array(items) => {
+                        for item in items {
+                            if let serde_json::Value::String(s) = item {
+                                *s = mask_secret(s);
+                            }
+                        }
+                    }
+                    other => mask_secrets(other),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(mask_secrets),
+        _ => {}
+    }
+}
+
+/// Print a prompt and read one trimmed line from stdin
+pub fn prompt_line(prompt: &str) -> anyhow::Result<String> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+/// Ask a yes/no question, anything but `y`/`yes` counts as no
+pub fn confirm(prompt: &str) -> anyhow::Result<bool> {
+    let answer = prompt_line(&format!("{} [y/N] ", prompt))?;
+    Ok(matches!(answer.to_ascii_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Print a unified diff between two texts, returns false when they are equal.
+///
+/// The diff goes to stderr when stdout carries JSON, like info messages.
+pub fn print_diff(old: &str, new: &str) -> bool {
+    let diff = TextDiff::from_lines(old, new);
+    if diff.ratio() == 1.0 {
+        return false;
+    }
+    let mut text = String::new();
+    for group in diff.grouped_ops(3) {
+        text.push_str("@@\n");
+        for op in group {
+            for change in diff.iter_changes(&op) {
+                let sign = match change.tag() {
+                    ChangeTag::Delete => "-",
+                    ChangeTag::Insert => "+",
+                    ChangeTag::Equal => " ",
+                };
+                text.push_str(&format!("{}{}", sign, change));
+                if change.missing_newline() {
+                    text.push('\n');
+                }
+            }
+        }
+    }
+    if output::messages_to_stderr() {
+        eprint!("{}", text);
+    } else {
+        print!("{}", text);
+    }
+    true
+}
+
+/// Options to write a file only the current user can read
+pub fn private_open_options() -> fs::OpenOptions {
+    let mut options = fs::OpenOptions::new();
+    options.write(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+}
+
+/// Open `content` in `$VISUAL`/`$EDITOR` and return the edited text
+pub async fn edit_in_editor(content: &str, extension: &str) -> anyhow::Result<String> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| {
+            if cfg!(target_os = "windows") {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+
+    let path = env::temp_dir().join(format!(
+        "astrbot-edit-{}-{}.{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis()),
+        extension
+    ));
+    // The content may hold credentials, and a file planted at this
+    // predictable path must not be reused
+    private_open_options()
+        .create_new(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    vprintln!("Opening {} with `{}`", path.display(), editor);
+
+    // The editor may carry arguments, e.g. `code --wait`
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(parts)
+        .arg(&path)
+        .status()
+        .await
+        .with_context(|| format!("Failed to launch editor `{}`", editor));
+
+    let edited = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+    if !status?.success() {
+        anyhow::bail!("Editor `{}` exited with an error", editor);
+    }
+    Ok(edited?)
+}
+
+/// Edit a JSON object in the editor until it parses and `check` reports no
+/// problems, the user may give up with a validation error
+pub async fn edit_json<F>(value: &serde_json::Value, check: F) -> anyhow::Result<serde_json::Value>
+where
+    F: Fn(&serde_json::Value) -> Vec<String>,
+{
+    let mut text = serde_json::to_string_pretty(value)?;
+    loop {
+        text = edit_in_editor(&text, "json").await?;
+        let problem = match serde_json::from_str::<serde_json::Value>(&text) {
+            Ok(edited @ serde_json::Value::Object(_)) => {
+                let problems = check(&edited);
+                if problems.is_empty() {
+                    return Ok(edited);
+                }
+                format!("Invalid config:\n  {}", problems.join("\n  "))
+            }
+            Ok(_) => "The config must be a JSON object".to_string(),
+            Err(e) => format!("Invalid JSON: {}", e),
+        };
+        eprintln!("{}", problem);
+        if !confirm("Edit again?")? {
+            anyhow::bail!(CliError::Validation(problem));
+        }
+    }
+}
+
+/// Show the diff between two JSON values and ask before applying it.
+///
+/// Returns false when there is nothing to apply or the user declined.
+pub fn review_json_changes(
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+    yes: bool,
+) -> anyhow::Result<bool> {
+    let old = serde_json::to_string_pretty(old)?;
+    let new = serde_json::to_string_pretty(new)?;
+    if !print_diff(&old, &new) {
+        output::print_message("No changes");
+        return Ok(false);
+    }
+    if !yes && !confirm("Apply these changes?")? {
+        output::print_message("Aborted");
+        return Ok(false);
+    }
+    Ok(true)
+}
@@ -15,20 +15,29 @@ pub fn is_verbose() -> bool {
     *VERBOSE.get().unwrap_or(&false)
 }
 
-/// Print a formatted message in verbose mode
+/// Print a formatted message in verbose mode, to stderr in JSON mode
 #[macro_export]
 macro_rules! vprintln {
     ($($arg:tt)*) => {
         if $crate::verbose::is_verbose() {
-            println!("[VERBOSE] {}", format!($($arg)*));
+            if $crate::output::messages_to_stderr() {
+                eprintln!("[VERBOSE] {}", format!($($arg)*));
+            } else {
+                println!("[VERBOSE] {}", format!($($arg)*));
+            }
         }
     };
 }
 
-/// Print a formatted message in info mode
+/// Print a formatted message in info mode, to stderr in JSON mode or when
+/// stdout carries a report
 #[macro_export]
 macro_rules! iprintln {
     ($($arg:tt)*) => {
-        println!("[INFO]    {}", format!($($arg)*));
+        if $crate::output::messages_to_stderr() {
+            eprintln!("[INFO]    {}", format!($($arg)*));
+        } else {
+            println!("[INFO]    {}", format!($($arg)*));
+        }
     };
 }